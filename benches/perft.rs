@@ -0,0 +1,58 @@
+//! Compares full perft against bulk counting at the last ply, and copying
+//! the board at every node against make/unmake.
+//!
+//! Run with `cargo bench --bench perft`.
+
+use std::time::{Duration, Instant};
+
+use checkers_perft::{
+    board::{Board, Color},
+    fen::parse_fen,
+    perft::{perft, perft_bulk, perft_make_unmake},
+};
+
+const RUNS: u32 = 3;
+
+fn time<F: Fn() -> u64>(f: F) -> (u64, Duration) {
+    let mut best = Duration::MAX;
+    let mut nodes = 0;
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        nodes = f();
+        best = best.min(start.elapsed());
+    }
+    (nodes, best)
+}
+
+fn bench(name: &str, color: Color, board: &Board, depth: u32) {
+    let (nodes, full) = time(|| perft(color, board, depth));
+    let (bulk_nodes, bulk) = time(|| perft_bulk(color, board, depth));
+    let (unmake_nodes, unmake) = time(|| perft_make_unmake(color, board, depth));
+    assert_eq!(
+        nodes, bulk_nodes,
+        "{name}: bulk counting changed the result"
+    );
+    assert_eq!(
+        nodes, unmake_nodes,
+        "{name}: make/unmake changed the result"
+    );
+    println!(
+        "{name} perft({depth}) = {nodes}: full {:.3}s, bulk {:.3}s, speedup {:.2}x",
+        full.as_secs_f64(),
+        bulk.as_secs_f64(),
+        full.as_secs_f64() / bulk.as_secs_f64()
+    );
+    println!(
+        "{name} perft({depth}) = {nodes}: copy-make {:.3}s, make/unmake {:.3}s, speedup {:.2}x",
+        full.as_secs_f64(),
+        unmake.as_secs_f64(),
+        full.as_secs_f64() / unmake.as_secs_f64()
+    );
+}
+
+fn main() {
+    bench("initial", Color::Black, &Board::new(), 10);
+
+    let (board, color) = parse_fen("W:W18,24,27,28,K10,K15:B12,16,20,K22,K25,K29").unwrap();
+    bench("kings", color, &board, 8);
+}
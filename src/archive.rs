@@ -0,0 +1,126 @@
+use rayon::prelude::*;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use crate::error::Result;
+use crate::game_json::{Game, JsonGameReader};
+use crate::pdn::PdnReader;
+use crate::replay::{validate, ReplayError};
+
+/// Games read one at a time from a file: PDN when the extension is `.pdn`,
+/// otherwise a JSON array, a single JSON game or JSON Lines.
+pub enum GameReader {
+    Json(JsonGameReader<BufReader<File>>),
+    Pdn(PdnReader<BufReader<File>>),
+}
+
+impl GameReader {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("pdn"))
+        {
+            Ok(GameReader::Pdn(PdnReader::from_file(path)?))
+        } else {
+            Ok(GameReader::Json(JsonGameReader::from_file(path)?))
+        }
+    }
+}
+
+impl Iterator for GameReader {
+    type Item = Result<Game>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            GameReader::Json(reader) => reader.next(),
+            GameReader::Pdn(reader) => reader.next().map(|game| Ok(game?)),
+        }
+    }
+}
+
+/// A game that could not be read or that contains an illegal move.
+#[derive(Debug)]
+pub struct GameFailure {
+    /// Position of the game in its source, counting from 1.
+    pub index: usize,
+    /// The game's `Event` header, when it could be read.
+    pub event: Option<String>,
+    pub error: ReplayError,
+}
+
+#[derive(Debug, Default)]
+pub struct ValidationSummary {
+    pub games: usize,
+    pub passed: usize,
+    /// Failures in the order the games were read.
+    pub failures: Vec<GameFailure>,
+}
+
+impl ValidationSummary {
+    fn merge(mut self, other: ValidationSummary) -> Self {
+        self.games += other.games;
+        self.passed += other.passed;
+        self.failures.extend(other.failures);
+        self
+    }
+}
+
+/// Validates every game from `games` in parallel, reading them as workers
+/// become free so only a few games are held in memory at once.
+pub fn validate_games<I>(games: I) -> ValidationSummary
+where
+    I: Iterator<Item = Result<Game>> + Send,
+{
+    let mut summary = games
+        .enumerate()
+        .par_bridge()
+        .map(|(i, game)| {
+            let (event, result) = match game {
+                Ok(game) => (game.header("Event").map(str::to_string), validate(&game)),
+                Err(e) => (None, Err(ReplayError::from(e))),
+            };
+            let mut summary = ValidationSummary {
+                games: 1,
+                ..ValidationSummary::default()
+            };
+            match result {
+                Ok(()) => summary.passed = 1,
+                Err(error) => summary.failures.push(GameFailure {
+                    index: i + 1,
+                    event,
+                    error,
+                }),
+            }
+            summary
+        })
+        .reduce(ValidationSummary::default, ValidationSummary::merge);
+    summary.failures.sort_by_key(|f| f.index);
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::archive::validate_games;
+    use crate::pdn::PdnReader;
+    use crate::replay::ReplayError;
+
+    #[test]
+    fn test_validate_games_summary() {
+        let pdn = "[Event \"a\"]\n1. 11-15 23-19 *\n\n\
+                   [Event \"b\"]\n1. 11-15 23-19 2. 15-19 *\n\n\
+                   [Event \"c\"]\n1. 11x25 *\n\n\
+                   [Event \"d\"]\n1. 9-14 *\n";
+        let games = PdnReader::new(pdn.as_bytes()).map(|game| Ok(game?));
+        let summary = validate_games(games);
+        assert_eq!(summary.games, 4);
+        assert_eq!(summary.passed, 2);
+        assert_eq!(summary.failures.len(), 2);
+        assert_eq!(summary.failures[0].index, 2);
+        assert_eq!(summary.failures[0].event.as_deref(), Some("b"));
+        assert!(matches!(summary.failures[0].error, ReplayError::Illegal(_)));
+        assert_eq!(summary.failures[1].index, 3);
+        assert!(matches!(summary.failures[1].error, ReplayError::Invalid(_)));
+    }
+}
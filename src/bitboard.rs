@@ -1,10 +1,12 @@
+use crate::error::Result;
+use crate::square::Square;
 use crate::translate::{bit_index_notation, notation_bit_index};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct Bitboard(u64);
 
 impl Bitboard {
-    pub fn new() -> Self {
+    pub const fn new() -> Self {
         Bitboard(0)
     }
 
@@ -21,28 +23,43 @@ impl Bitboard {
         Bitboard(1 << ((s + d) / 2))
     }
 
-    pub fn from_notation_vector(notation: &[u8]) -> Self {
+    pub fn try_from_notation_vector(notation: &[u8]) -> Result<Self> {
         let mut bitboard = Bitboard::new();
         for &n in notation {
-            let bit_index = notation_bit_index(n);
+            let bit_index = notation_bit_index(n)?;
             bitboard.0 |= 1 << bit_index;
         }
-        bitboard
+        Ok(bitboard)
     }
 
-    pub fn as_notation_vector(&self) -> Vec<u8> {
+    pub fn try_as_notation_vector(&self) -> Result<Vec<u8>> {
         let mut notation = Vec::new();
         for piece in self {
             let bit_index = piece.trailing_zeros() as u8;
-            notation.push(bit_index_notation(bit_index));
+            notation.push(bit_index_notation(bit_index)?);
         }
-        notation
+        Ok(notation)
+    }
+
+    /// Squares of the set bits in bit order, skipping bits that are not
+    /// playable squares.
+    pub fn squares(&self) -> impl Iterator<Item = Square> {
+        self.into_iter()
+            .filter_map(|bit| Square::from_bit_index(bit.trailing_zeros() as u8))
     }
 
     #[inline]
     pub fn len(&self) -> usize {
         self.0.count_ones() as usize
     }
+
+    /// The board turned half a turn, so square n moves to square 33 - n. The
+    /// padded layout is symmetric around bit 22.5: bit b maps to bit 45 - b,
+    /// ghost bits included.
+    #[inline]
+    pub fn rotated(&self) -> Bitboard {
+        Bitboard(self.0.reverse_bits() >> 18)
+    }
 }
 
 pub struct BitboardIter {
@@ -72,7 +89,7 @@ impl IntoIterator for Bitboard {
     }
 }
 
-impl<'a> IntoIterator for &'a Bitboard {
+impl IntoIterator for &Bitboard {
     type Item = u64;
     type IntoIter = BitboardIter;
 
@@ -161,8 +178,8 @@ impl From<u64> for Bitboard {
     }
 }
 
-impl Into<u64> for Bitboard {
-    fn into(self) -> u64 {
-        self.0
+impl From<Bitboard> for u64 {
+    fn from(value: Bitboard) -> Self {
+        value.0
     }
 }
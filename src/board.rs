@@ -1,6 +1,6 @@
 use std::fmt;
 
-use crate::{bitboard::Bitboard, r#move::Move};
+use crate::{bitboard::Bitboard, r#move::Move, square::Square};
 
 pub const PLAYABLE: u64 = 2130169298400;
 pub const BLACK_START: u64 = 2130035343360;
@@ -23,6 +23,87 @@ impl Color {
     }
 }
 
+pub const PIECES_PER_SIDE: usize = 12;
+
+/// A way in which a board cannot arise in a game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Violation {
+    /// Squares holding both a black and a white piece.
+    Overlap(Bitboard),
+    /// King bits on squares without a piece.
+    StrayKings(Bitboard),
+    /// Bits outside the 32 playable squares.
+    OffBoard(Bitboard),
+    /// Men on the row where they should have been crowned.
+    UncrownedMen {
+        color: Color,
+        squares: Bitboard,
+    },
+    TooManyPieces {
+        color: Color,
+        count: usize,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::Overlap(squares) => {
+                write!(f, "squares {:?} hold both colors", numbers(squares))
+            }
+            Violation::StrayKings(squares) => {
+                write!(f, "kings on empty squares {:?}", numbers(squares))
+            }
+            Violation::OffBoard(bits) => {
+                write!(f, "pieces outside the board: {:#x}", u64::from(*bits))
+            }
+            Violation::UncrownedMen { color, squares } => write!(
+                f,
+                "{color:?} men on their promotion row {:?}",
+                numbers(squares)
+            ),
+            Violation::TooManyPieces { color, count } => write!(
+                f,
+                "{color:?} has {count} pieces, more than {PIECES_PER_SIDE}"
+            ),
+        }
+    }
+}
+
+/// Every [`Violation`] found by [`Board::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidBoard(pub Vec<Violation>);
+
+impl fmt::Display for InvalidBoard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, violation) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{violation}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for InvalidBoard {}
+
+/// What [`Board::make_move`] changed beyond the move itself, needed to take
+/// the move back with [`Board::unmake_move`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Undo {
+    /// Captured pieces that were kings.
+    pub captured_kings: Bitboard,
+    /// The man crowned at the end of the move, if any.
+    pub promoted: Bitboard,
+}
+
+impl Undo {
+    pub fn is_promotion(&self) -> bool {
+        !self.promoted.is_empty()
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Board {
     pub bp: Bitboard,
@@ -30,6 +111,12 @@ pub struct Board {
     pub kings: Bitboard,
 }
 
+impl Default for Board {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Board {
     pub fn new() -> Self {
         Board {
@@ -43,6 +130,73 @@ impl Board {
         Board { bp, wp, kings }
     }
 
+    /// [`from_bitboards`](Self::from_bitboards), rejecting boards that
+    /// [`validate`](Self::validate) finds fault with.
+    pub fn try_from_bitboards(
+        bp: Bitboard,
+        wp: Bitboard,
+        kings: Bitboard,
+    ) -> Result<Self, InvalidBoard> {
+        let board = Board::from_bitboards(bp, wp, kings);
+        let violations = board.validate();
+        if violations.is_empty() {
+            Ok(board)
+        } else {
+            Err(InvalidBoard(violations))
+        }
+    }
+
+    /// The same position seen from the other side: the board is turned half a
+    /// turn and the colors are swapped, so Black to move on this board plays
+    /// like White to move on the flipped one.
+    ///
+    /// There is no left-right mirror to go with it: reflecting the board
+    /// across its middle file would put every piece on a light square.
+    pub fn flipped(&self) -> Board {
+        Board {
+            bp: self.wp.rotated(),
+            wp: self.bp.rotated(),
+            kings: self.kings.rotated(),
+        }
+    }
+
+    /// Everything that keeps this board from being a reachable position.
+    /// Squares in the other violations are limited to the playable ones.
+    pub fn validate(&self) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        let playable = Bitboard::from(PLAYABLE);
+
+        let off_board = (self.bp | self.wp | self.kings) & !playable;
+        if !off_board.is_empty() {
+            violations.push(Violation::OffBoard(off_board));
+        }
+        let overlap = self.bp & self.wp & playable;
+        if !overlap.is_empty() {
+            violations.push(Violation::Overlap(overlap));
+        }
+        let stray_kings = self.kings & !(self.bp | self.wp) & playable;
+        if !stray_kings.is_empty() {
+            violations.push(Violation::StrayKings(stray_kings));
+        }
+        for (color, pieces, promotion_row) in [
+            (Color::Black, self.bp, WHITE_KING_ROW),
+            (Color::White, self.wp, BLACK_KING_ROW),
+        ] {
+            let uncrowned = pieces & !self.kings & promotion_row;
+            if !uncrowned.is_empty() {
+                violations.push(Violation::UncrownedMen {
+                    color,
+                    squares: uncrowned,
+                });
+            }
+            let count = (pieces & playable).len();
+            if count > PIECES_PER_SIDE {
+                violations.push(Violation::TooManyPieces { color, count });
+            }
+        }
+        violations
+    }
+
     pub fn movers(&self, color: Color) -> Bitboard {
         match color {
             Color::Black => self.black_movers(),
@@ -90,6 +244,40 @@ impl Board {
         }
     }
 
+    /// Applies `m` and crowns a man reaching the last row, returning what is
+    /// needed to restore the board with [`unmake_move`](Self::unmake_move).
+    #[inline]
+    pub fn make_move(&mut self, color: Color, m: &Move) -> Undo {
+        let captured_kings = m.jumped & self.kings;
+        self.apply_move(color, m);
+        let promoted = self.promote_kings();
+        Undo {
+            captured_kings,
+            promoted,
+        }
+    }
+
+    /// Takes back `m`, which must be the last move made with
+    /// [`make_move`](Self::make_move).
+    #[inline]
+    pub fn unmake_move(&mut self, color: Color, m: &Move, undo: &Undo) {
+        self.kings ^= undo.promoted;
+        match color {
+            Color::Black => {
+                self.bp ^= m.movers;
+                self.wp ^= m.jumped;
+            }
+            Color::White => {
+                self.wp ^= m.movers;
+                self.bp ^= m.jumped;
+            }
+        }
+        if !(m.movers & self.kings).is_empty() {
+            self.kings ^= m.movers;
+        }
+        self.kings |= undo.captured_kings;
+    }
+
     pub fn promote_kings(&mut self) -> Bitboard {
         let black_kings = self.bp & WHITE_KING_ROW;
         let white_kings = self.wp & BLACK_KING_ROW;
@@ -201,14 +389,84 @@ impl Board {
     }
 }
 
+fn numbers(squares: &Bitboard) -> Vec<u8> {
+    squares.squares().map(Square::number).collect()
+}
+
 impl fmt::Display for Board {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
             "Board {{ black=[{:?}], white=[{:?}], kings=[{:?}] }}",
-            self.bp.as_notation_vector(),
-            self.wp.as_notation_vector(),
-            self.kings.as_notation_vector()
+            numbers(&self.bp),
+            numbers(&self.wp),
+            numbers(&self.kings)
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bitboard::Bitboard,
+        board::{Board, Color, Violation},
+        fen::{parse_fen, FenError},
+    };
+
+    #[test]
+    fn test_validate() {
+        assert!(Board::new().validate().is_empty());
+
+        let squares = |n: &[u8]| Bitboard::try_from_notation_vector(n).unwrap();
+        let board = Board::from_bitboards(
+            squares(&[1, 30]) | Bitboard::from(1 << 9),
+            squares(&[1, 2]),
+            squares(&[2, 3]),
+        );
+        assert_eq!(
+            board.validate(),
+            vec![
+                Violation::OffBoard(Bitboard::from(1 << 9)),
+                Violation::Overlap(squares(&[1])),
+                Violation::StrayKings(squares(&[3])),
+                Violation::UncrownedMen {
+                    color: Color::Black,
+                    squares: squares(&[30]),
+                },
+                Violation::UncrownedMen {
+                    color: Color::White,
+                    squares: squares(&[1]),
+                },
+            ]
+        );
+
+        let crowded = Board::from_bitboards(
+            squares(&(1..=28).collect::<Vec<_>>()),
+            Bitboard::new(),
+            Bitboard::new(),
+        );
+        assert_eq!(
+            crowded.validate(),
+            vec![Violation::TooManyPieces {
+                color: Color::Black,
+                count: 28
+            }]
+        );
+        assert!(Board::try_from_bitboards(crowded.bp, crowded.wp, crowded.kings).is_err());
+    }
+
+    #[test]
+    fn test_parsers_reject_invalid_boards() {
+        assert!(matches!(
+            parse_fen("B:W1-13:B20"),
+            Err(FenError::InvalidBoard(_))
+        ));
+        assert!(matches!(
+            parse_fen("B:W5:B30"),
+            Err(FenError::InvalidBoard(e)) if e.0 == vec![Violation::UncrownedMen {
+                color: Color::Black,
+                squares: Bitboard::try_from_notation_vector(&[30]).unwrap(),
+            }]
+        ));
+    }
+}
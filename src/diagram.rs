@@ -0,0 +1,385 @@
+use std::fmt;
+
+use crate::bitboard::Bitboard;
+use crate::board::{Board, Color, InvalidBoard};
+use crate::r#move::Move;
+use crate::square::Square;
+
+/// Characters used to draw a [`Diagram`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Style {
+    /// `b`/`B` for black men and kings, `w`/`W` for white ones and `.` for
+    /// empty playable squares.
+    #[default]
+    Ascii,
+    /// Draughts symbols for pieces and `·` for empty playable squares.
+    Unicode,
+}
+
+impl Style {
+    fn piece(self, color: Color, king: bool) -> char {
+        match (self, color, king) {
+            (Style::Ascii, Color::Black, false) => 'b',
+            (Style::Ascii, Color::Black, true) => 'B',
+            (Style::Ascii, Color::White, false) => 'w',
+            (Style::Ascii, Color::White, true) => 'W',
+            (Style::Unicode, Color::Black, false) => '⛂',
+            (Style::Unicode, Color::Black, true) => '⛃',
+            (Style::Unicode, Color::White, false) => '⛀',
+            (Style::Unicode, Color::White, true) => '⛁',
+        }
+    }
+
+    fn empty(self) -> char {
+        match self {
+            Style::Ascii => '.',
+            Style::Unicode => '·',
+        }
+    }
+}
+
+/// An 8×8 drawing of a board, one row per line with trailing spaces
+/// trimmed. Every square takes four columns; squares a highlighted move
+/// starts or ends on are wrapped in `[ ]` and captured squares in `( )`.
+///
+/// Built with [`Board::diagram`]; by default White is at the bottom, as in
+/// printed diagrams where square 1 is in the top left.
+#[derive(Debug, Clone, Copy)]
+pub struct Diagram<'a> {
+    board: &'a Board,
+    style: Style,
+    numbers: bool,
+    highlight: Option<Move>,
+    bottom: Color,
+}
+
+impl Board {
+    pub fn diagram(&self) -> Diagram<'_> {
+        Diagram {
+            board: self,
+            style: Style::default(),
+            numbers: false,
+            highlight: None,
+            bottom: Color::White,
+        }
+    }
+}
+
+impl<'a> Diagram<'a> {
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Shows the number of every empty playable square.
+    pub fn with_numbers(mut self, numbers: bool) -> Self {
+        self.numbers = numbers;
+        self
+    }
+
+    /// Marks the squares `m` moves between and the pieces it captures.
+    pub fn with_highlight(mut self, m: &Move) -> Self {
+        self.highlight = Some(*m);
+        self
+    }
+
+    /// Which side is drawn at the bottom of the diagram.
+    pub fn with_bottom(mut self, bottom: Color) -> Self {
+        self.bottom = bottom;
+        self
+    }
+
+    fn cell(&self, square: Square) -> String {
+        let bb = square.bitboard();
+        let king = !(self.board.kings & bb).is_empty();
+        let content = if !(self.board.bp & bb).is_empty() {
+            format!("{:>2}", self.style.piece(Color::Black, king))
+        } else if !(self.board.wp & bb).is_empty() {
+            format!("{:>2}", self.style.piece(Color::White, king))
+        } else if self.numbers {
+            format!("{:>2}", square.number())
+        } else {
+            format!("{:>2}", self.style.empty())
+        };
+
+        let (moved, captured) = match &self.highlight {
+            Some(m) => {
+                let landing = m.path.last() == Some(square.number());
+                (
+                    landing || !(m.movers & bb).is_empty(),
+                    !(m.jumped & bb).is_empty(),
+                )
+            }
+            None => (false, false),
+        };
+        match (moved, captured) {
+            (true, _) => format!("[{content}]"),
+            (_, true) => format!("({content})"),
+            _ => format!(" {content} "),
+        }
+    }
+}
+
+impl fmt::Display for Diagram<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in 0..8u8 {
+            // row 0 holds squares 1-4, at the top when White is at the bottom
+            let row = match self.bottom {
+                Color::White => line,
+                Color::Black => 7 - line,
+            };
+            let mut text = String::new();
+            for column in 0..8u8 {
+                let col = match self.bottom {
+                    Color::White => column,
+                    Color::Black => 7 - column,
+                };
+                match Square::from_row_col(row, col) {
+                    Some(square) => text.push_str(&self.cell(square)),
+                    None => text.push_str("    "),
+                }
+            }
+            writeln!(f, "{}", text.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiagramError {
+    /// The diagram does not have exactly eight rows.
+    RowCount(usize),
+    /// A row has neither eight squares nor just the four dark ones; rows
+    /// count from 1 at the top.
+    RowLength {
+        row: usize,
+        length: usize,
+    },
+    InvalidPiece {
+        row: usize,
+        column: usize,
+        piece: char,
+    },
+    /// A piece sits on a light square, which is never played on.
+    LightSquare {
+        row: usize,
+        column: usize,
+    },
+    InvalidBoard(InvalidBoard),
+}
+
+impl fmt::Display for DiagramError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiagramError::RowCount(n) => write!(f, "expected 8 rows, found {n}"),
+            DiagramError::RowLength { row, length } => {
+                write!(f, "row {row}: expected 8 or 4 squares, found {length}")
+            }
+            DiagramError::InvalidPiece { row, column, piece } => {
+                write!(f, "row {row}, column {column}: invalid piece {piece:?}")
+            }
+            DiagramError::LightSquare { row, column } => {
+                write!(f, "row {row}, column {column}: piece on a light square")
+            }
+            DiagramError::InvalidBoard(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for DiagramError {}
+
+/// Parses an 8×8 text diagram into a board, with square 1 in the top row as
+/// in [`Diagram`]'s default orientation. Each row has one character per
+/// square: `b`/`B` for black men and kings, `w`/`W` for white ones and `.`,
+/// `-` or `_` for empty squares. Whitespace within rows and blank lines are
+/// ignored, so diagrams can be indented in raw strings.
+///
+/// A row may also list only its four dark squares, which reads the output of
+/// [`Board::diagram`] in either [`Style`] as long as White is at the bottom
+/// and no numbers or highlights are drawn.
+pub fn parse_diagram(text: &str) -> Result<Board, DiagramError> {
+    let rows: Vec<Vec<char>> = text
+        .lines()
+        .map(|line| line.chars().filter(|c| !c.is_whitespace()).collect())
+        .filter(|row: &Vec<char>| !row.is_empty())
+        .collect();
+    if rows.len() != 8 {
+        return Err(DiagramError::RowCount(rows.len()));
+    }
+
+    let mut black = Bitboard::new();
+    let mut white = Bitboard::new();
+    let mut kings = Bitboard::new();
+    for (row, squares) in rows.iter().enumerate() {
+        let columns: Vec<usize> = match squares.len() {
+            8 => (0..8).collect(),
+            4 => (0..8).filter(|col| (row + col) % 2 == 1).collect(),
+            length => {
+                return Err(DiagramError::RowLength {
+                    row: row + 1,
+                    length,
+                })
+            }
+        };
+        for (col, &piece) in columns.into_iter().zip(squares) {
+            let (pieces, king) = match piece {
+                '.' | '-' | '_' | '·' => continue,
+                'b' | '⛂' => (&mut black, false),
+                'B' | '⛃' => (&mut black, true),
+                'w' | '⛀' => (&mut white, false),
+                'W' | '⛁' => (&mut white, true),
+                _ => {
+                    return Err(DiagramError::InvalidPiece {
+                        row: row + 1,
+                        column: col + 1,
+                        piece,
+                    })
+                }
+            };
+            let Some(square) = Square::from_row_col(row as u8, col as u8) else {
+                return Err(DiagramError::LightSquare {
+                    row: row + 1,
+                    column: col + 1,
+                });
+            };
+            *pieces |= square.bitboard();
+            if king {
+                kings |= square.bitboard();
+            }
+        }
+    }
+
+    Board::try_from_bitboards(black, white, kings).map_err(DiagramError::InvalidBoard)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, Color};
+    use crate::diagram::{parse_diagram, DiagramError, Style};
+    use crate::fen::parse_fen;
+    use crate::notation::parse_move;
+
+    #[test]
+    fn test_initial_position() {
+        let expected = [
+            "      b       b       b       b",
+            "  b       b       b       b",
+            "      b       b       b       b",
+            "  .       .       .       .",
+            "      .       .       .       .",
+            "  w       w       w       w",
+            "      w       w       w       w",
+            "  w       w       w       w",
+        ];
+        assert_eq!(
+            Board::new().diagram().to_string(),
+            expected.join("\n") + "\n"
+        );
+    }
+
+    #[test]
+    fn test_numbers_and_orientation() {
+        let (board, _) = parse_fen("B:WK32:B1").unwrap();
+        let diagram = board.diagram().with_numbers(true).to_string();
+        let lines: Vec<&str> = diagram.lines().collect();
+        assert_eq!(lines[0], "      b       2       3       4");
+        assert_eq!(lines[7], " 29      30      31       W");
+
+        let flipped = board
+            .diagram()
+            .with_numbers(true)
+            .with_bottom(Color::Black)
+            .to_string();
+        let lines: Vec<&str> = flipped.lines().collect();
+        assert_eq!(lines[0], "      W      31      30      29");
+        assert_eq!(lines[7], "  4       3       2       b");
+    }
+
+    #[test]
+    fn test_highlight() {
+        let (board, color) = parse_fen("W:W27:B8,15,23").unwrap();
+        let m = parse_move("27x4", &board, color).unwrap();
+        let diagram = board.diagram().with_highlight(&m).to_string();
+        let lines: Vec<&str> = diagram.lines().collect();
+        assert_eq!(lines[0], "      .       .       .     [ .]");
+        assert_eq!(lines[1], "  .       .       .     ( b)");
+        assert_eq!(lines[3], "  .       .     ( b)      .");
+        assert_eq!(lines[6], "      .       .     [ w]      .");
+    }
+
+    #[test]
+    fn test_unicode() {
+        let (board, _) = parse_fen("B:WK32:BK1").unwrap();
+        let diagram = board.diagram().with_style(Style::Unicode).to_string();
+        assert!(diagram.starts_with("      ⛃       ·"));
+        assert!(diagram.trim_end().ends_with('⛁'));
+    }
+
+    #[test]
+    fn test_parse_diagram() {
+        let board = parse_diagram(
+            "
+            ._._._._
+            _._._._.
+            ._.B._._
+            _._._._.
+            ._._.w._
+            _._._.W.
+            .b._._._
+            _._._._.
+            ",
+        )
+        .unwrap();
+        let (expected, _) = parse_fen("B:W19,K24:BK10,25").unwrap();
+        assert_eq!(board, expected);
+    }
+
+    #[test]
+    fn test_parse_rendered_diagram() {
+        let (board, _) = parse_fen("B:W19,K24,32:BK10,1,25").unwrap();
+        for style in [Style::Ascii, Style::Unicode] {
+            let diagram = board.diagram().with_style(style).to_string();
+            assert_eq!(parse_diagram(&diagram), Ok(board));
+        }
+        assert_eq!(
+            parse_diagram(&Board::new().diagram().to_string()),
+            Ok(Board::new())
+        );
+    }
+
+    #[test]
+    fn test_parse_diagram_errors() {
+        let empty = "........\n".repeat(8);
+        assert_eq!(
+            parse_diagram(&empty),
+            Ok(Board::from_bitboards(
+                Default::default(),
+                Default::default(),
+                Default::default()
+            ))
+        );
+        assert_eq!(
+            parse_diagram(&"........\n".repeat(7)),
+            Err(DiagramError::RowCount(7))
+        );
+        let short = empty.replacen("........", ".......", 1);
+        assert_eq!(
+            parse_diagram(&short),
+            Err(DiagramError::RowLength { row: 1, length: 7 })
+        );
+        let invalid = empty.replacen("........", ".x......", 1);
+        assert_eq!(
+            parse_diagram(&invalid),
+            Err(DiagramError::InvalidPiece {
+                row: 1,
+                column: 2,
+                piece: 'x'
+            })
+        );
+        let light = empty.replacen("........", "b.......", 1);
+        assert_eq!(
+            parse_diagram(&light),
+            Err(DiagramError::LightSquare { row: 1, column: 1 })
+        );
+    }
+}
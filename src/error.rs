@@ -0,0 +1,85 @@
+use std::fmt;
+use std::io;
+
+use crate::fen::FenError;
+use crate::notation::NotationError;
+use crate::pdn::PdnError;
+
+/// Errors from loading games and translating squares and moves.
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Fen(FenError),
+    Pdn(PdnError),
+    /// A square number outside 1..=32.
+    InvalidSquare(u8),
+    /// A bit index that is not one of the 32 playable squares.
+    InvalidBitIndex(u8),
+    /// Algebraic coordinates that do not name a dark square.
+    InvalidCoordinate(String),
+    MalformedMove(String),
+    /// A move in text notation that is malformed or not legal on its board.
+    Notation(NotationError),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "I/O error: {e}"),
+            Error::Json(e) => write!(f, "invalid JSON: {e}"),
+            Error::Fen(e) => write!(f, "invalid FEN: {e}"),
+            Error::Pdn(e) => write!(f, "invalid PDN: {e}"),
+            Error::InvalidSquare(n) => write!(f, "invalid square: {n}"),
+            Error::InvalidBitIndex(i) => write!(f, "invalid bit index: {i}"),
+            Error::InvalidCoordinate(s) => write!(f, "invalid coordinate: {s:?}"),
+            Error::MalformedMove(s) => write!(f, "malformed move: {s}"),
+            Error::Notation(e) => write!(f, "invalid move: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Json(e) => Some(e),
+            Error::Fen(e) => Some(e),
+            Error::Pdn(e) => Some(e),
+            Error::Notation(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Json(e)
+    }
+}
+
+impl From<FenError> for Error {
+    fn from(e: FenError) -> Self {
+        Error::Fen(e)
+    }
+}
+
+impl From<PdnError> for Error {
+    fn from(e: PdnError) -> Self {
+        Error::Pdn(e)
+    }
+}
+
+impl From<NotationError> for Error {
+    fn from(e: NotationError) -> Self {
+        Error::Notation(e)
+    }
+}
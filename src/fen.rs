@@ -0,0 +1,195 @@
+use std::fmt;
+
+use crate::bitboard::Bitboard;
+use crate::board::{Board, Color, InvalidBoard};
+use crate::square::Square;
+
+pub const INITIAL_FEN: &str = "B:W21-32:B1-12";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FenError {
+    MissingSideToMove,
+    InvalidSideToMove(String),
+    InvalidColor(String),
+    InvalidSquare(String),
+    DuplicateSquare(u8),
+    InvalidBoard(InvalidBoard),
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FenError::MissingSideToMove => write!(f, "missing side to move"),
+            FenError::InvalidSideToMove(s) => write!(f, "invalid side to move: {s:?}"),
+            FenError::InvalidColor(s) => write!(f, "invalid piece list: {s:?}"),
+            FenError::InvalidSquare(s) => write!(f, "invalid square: {s:?}"),
+            FenError::DuplicateSquare(n) => write!(f, "square {n} is occupied twice"),
+            FenError::InvalidBoard(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for FenError {}
+
+/// Parses a PDN FEN string such as `B:W18,24,K10:B12,16,K22` into a board and
+/// the side to move. The string may be wrapped in a `[FEN "..."]` tag, and
+/// piece lists may use ranges like `1-12`.
+pub fn parse_fen(fen: &str) -> Result<(Board, Color), FenError> {
+    let fen = unwrap_tag(fen.trim());
+    let fen = fen.trim().trim_end_matches('.');
+    let mut fields = fen.split(':');
+
+    let color = match fields.next().map(str::trim) {
+        None | Some("") => return Err(FenError::MissingSideToMove),
+        Some("B") | Some("b") => Color::Black,
+        Some("W") | Some("w") => Color::White,
+        Some(s) => return Err(FenError::InvalidSideToMove(s.to_string())),
+    };
+
+    let mut bp = Bitboard::new();
+    let mut wp = Bitboard::new();
+    let mut kings = Bitboard::new();
+    for field in fields {
+        let field = field.trim();
+        let (piece_color, list) = match field.chars().next() {
+            Some('B') | Some('b') => (Color::Black, &field[1..]),
+            Some('W') | Some('w') => (Color::White, &field[1..]),
+            _ => return Err(FenError::InvalidColor(field.to_string())),
+        };
+        for item in list.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let (is_king, squares) = match item.strip_prefix(['K', 'k']) {
+                Some(rest) => (true, rest),
+                None => (false, item),
+            };
+            for square in parse_squares(squares)? {
+                let n = square.number();
+                let square = square.bitboard();
+                if !((bp | wp) & square).is_empty() {
+                    return Err(FenError::DuplicateSquare(n));
+                }
+                match piece_color {
+                    Color::Black => bp |= square,
+                    Color::White => wp |= square,
+                }
+                if is_king {
+                    kings |= square;
+                }
+            }
+        }
+    }
+
+    let board = Board::try_from_bitboards(bp, wp, kings).map_err(FenError::InvalidBoard)?;
+    Ok((board, color))
+}
+
+/// Writes a board and side to move as a PDN FEN string, listing men before
+/// kings in ascending square order for each color.
+pub fn to_fen(board: &Board, color: Color) -> String {
+    let side = match color {
+        Color::Black => "B",
+        Color::White => "W",
+    };
+    format!(
+        "{}:W{}:B{}",
+        side,
+        piece_list(board.wp, board.kings),
+        piece_list(board.bp, board.kings)
+    )
+}
+
+fn unwrap_tag(fen: &str) -> &str {
+    match fen
+        .strip_prefix('[')
+        .and_then(|s| s.strip_suffix(']'))
+        .and_then(|s| s.trim().strip_prefix("FEN"))
+    {
+        Some(inner) => inner.trim().trim_matches('"'),
+        None => fen.trim_matches('"'),
+    }
+}
+
+fn parse_square(s: &str) -> Result<Square, FenError> {
+    s.trim()
+        .parse::<u8>()
+        .ok()
+        .and_then(Square::new)
+        .ok_or_else(|| FenError::InvalidSquare(s.to_string()))
+}
+
+fn parse_squares(s: &str) -> Result<Vec<Square>, FenError> {
+    match s.split_once('-') {
+        Some((from, to)) => {
+            let from = parse_square(from)?;
+            let to = parse_square(to)?;
+            if from > to {
+                return Err(FenError::InvalidSquare(s.to_string()));
+            }
+            Ok((from.number()..=to.number())
+                .filter_map(Square::new)
+                .collect())
+        }
+        None => Ok(vec![parse_square(s)?]),
+    }
+}
+
+fn piece_list(pieces: Bitboard, kings: Bitboard) -> String {
+    let mut men: Vec<u8> = (pieces & !kings).squares().map(Square::number).collect();
+    let mut crowned: Vec<u8> = (pieces & kings).squares().map(Square::number).collect();
+    men.sort_unstable();
+    crowned.sort_unstable();
+    men.iter()
+        .map(|n| n.to_string())
+        .chain(crowned.iter().map(|n| format!("K{n}")))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bitboard::Bitboard,
+        board::{Board, Color},
+        fen::{parse_fen, to_fen, FenError, INITIAL_FEN},
+    };
+
+    #[test]
+    fn test_fen_initial_position() {
+        let (board, color) = parse_fen(INITIAL_FEN).unwrap();
+        assert_eq!(board, Board::new());
+        assert_eq!(color, Color::Black);
+        assert_eq!(
+            to_fen(&board, color),
+            "B:W21,22,23,24,25,26,27,28,29,30,31,32:B1,2,3,4,5,6,7,8,9,10,11,12"
+        );
+    }
+
+    #[test]
+    fn test_fen_tag_round_trip() {
+        let fen = "B:W18,24,27,28,K10,K15:B12,16,20,K22,K25,K29";
+        let (board, color) = parse_fen(&format!("[FEN \"{fen}\"]")).unwrap();
+        assert_eq!(color, Color::Black);
+        assert_eq!(
+            board.wp,
+            Bitboard::try_from_notation_vector(&[18, 24, 27, 28, 10, 15]).unwrap()
+        );
+        assert_eq!(
+            board.kings,
+            Bitboard::try_from_notation_vector(&[10, 15, 22, 25, 29]).unwrap()
+        );
+        assert_eq!(to_fen(&board, color), fen);
+    }
+
+    #[test]
+    fn test_fen_errors() {
+        assert_eq!(parse_fen(""), Err(FenError::MissingSideToMove));
+        assert_eq!(
+            parse_fen("X:W1:B2"),
+            Err(FenError::InvalidSideToMove("X".to_string()))
+        );
+        assert_eq!(
+            parse_fen("W:W33:B2"),
+            Err(FenError::InvalidSquare("33".to_string()))
+        );
+        assert_eq!(parse_fen("W:W1:B1"), Err(FenError::DuplicateSquare(1)));
+    }
+}
@@ -1,17 +1,22 @@
-use serde::{Deserialize, Serialize};
-use std::fs;
+use serde::{de::Error as _, Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
 use std::path::Path;
 
 use crate::bitboard::Bitboard;
-use crate::r#move::Move;
+use crate::board::{Board, Color};
+use crate::error::{Error, Result};
+use crate::fen::{parse_fen, FenError};
+use crate::r#move::{Move, Path as MovePath, MAX_PATH};
+use crate::square::Square;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Header {
     pub name: String,
     pub value: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MoveKind {
     #[serde(rename = "simple")]
     Simple,
@@ -19,65 +24,297 @@ pub enum MoveKind {
     Jump,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MoveDetail {
     pub kind: MoveKind,
     pub moves: Vec<u8>,
 }
 
 impl MoveDetail {
-    pub fn into_move(self) -> Move {
+    /// Converts the recorded squares into a [`Move`], checking that every
+    /// square exists and that each jump spans exactly one piece.
+    pub fn into_move(self) -> Result<Move> {
+        if self.moves.len() < 2 || self.moves.len() > MAX_PATH + 1 {
+            return Err(Error::MalformedMove(self.notation()));
+        }
+        let squares = self
+            .moves
+            .iter()
+            .map(|&n| Square::try_from(n))
+            .collect::<Result<Vec<_>>>()?;
         match self.kind {
-            MoveKind::Simple => self.simple(),
-            MoveKind::Jump => self.jumps(),
+            MoveKind::Simple if squares.len() == 2 => self.simple(&squares),
+            MoveKind::Simple => Err(Error::MalformedMove(self.notation())),
+            MoveKind::Jump => self.jumps(&squares),
         }
     }
 
-    fn simple(self) -> Move {
-        Move::new(Bitboard::from_notation_vector(&self.moves), Bitboard::new())
+    /// The recorded squares joined with `-` or `x`.
+    pub fn notation(&self) -> String {
+        let separator = match self.kind {
+            MoveKind::Simple => "-",
+            MoveKind::Jump => "x",
+        };
+        self.moves
+            .iter()
+            .map(u8::to_string)
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+
+    fn simple(&self, squares: &[Square]) -> Result<Move> {
+        if !Square::is_step(squares[0], squares[1]) {
+            return Err(Error::MalformedMove(self.notation()));
+        }
+        Ok(Move::with_path(
+            squares[0].bitboard() | squares[1].bitboard(),
+            Bitboard::new(),
+            MovePath::try_from_notation_vector(&self.moves[1..])?,
+        ))
     }
 
-    fn jumps(self) -> Move {
-        let len = self.moves.len();
-        let dest = self.moves[len - 1];
-        let start = self.moves[0];
-        let movers = Bitboard::from_notation_vector(&[start, dest]);
+    fn jumps(&self, squares: &[Square]) -> Result<Move> {
+        let start = squares[0].bitboard();
+        let dest = squares[squares.len() - 1].bitboard();
+        let movers = start ^ dest;
         let mut jumpers = Bitboard::new();
-        let mut last: Option<Bitboard> = None;
-        for n in self.moves {
-            let n_bb = Bitboard::from_notation_vector(&[n]);
-            if let Some(last_bb) = last {
-                jumpers |= last_bb.jumped(n_bb);
-            }
-            last = Some(n_bb);
+        for pair in squares.windows(2) {
+            let jumped = Square::jumped_between(pair[0], pair[1])
+                .ok_or_else(|| Error::MalformedMove(self.notation()))?;
+            jumpers |= jumped.bitboard();
         }
-        Move::new(movers, jumpers)
+        Ok(Move::with_path(
+            movers,
+            jumpers,
+            MovePath::try_from_notation_vector(&self.moves[1..])?,
+        ))
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MovePair {
     pub number: u32,
-    pub black: MoveDetail,
+    /// Missing when the game starts from a position with White to move.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub black: Option<MoveDetail>,
     pub white: Option<MoveDetail>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Game {
     pub headers: Vec<Header>,
     pub moves: Vec<MovePair>,
 }
 
 impl Game {
-    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, serde_json::Error> {
-        let data = fs::read_to_string(path).expect("Unable to read file");
-        serde_json::from_str(&data)
+    /// Value of the first header called `name`.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|h| h.name == name)
+            .map(|h| h.value.as_str())
+    }
+
+    /// Position the game starts from, taken from its `FEN` header if present.
+    pub fn starting_position(&self) -> std::result::Result<(Board, Color), FenError> {
+        match self.header("FEN") {
+            Some(fen) => parse_fen(fen),
+            None => Ok((Board::new(), Color::Black)),
+        }
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let data = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&data)?)
     }
 }
 
-pub fn parse_games_from_json_array_file<P: AsRef<Path>>(
-    path: P,
-) -> Result<Vec<Game>, serde_json::Error> {
-    let data = fs::read_to_string(path).expect("Unable to read file");
-    serde_json::from_str(&data)
+pub fn parse_games_from_json_array_file<P: AsRef<Path>>(path: P) -> Result<Vec<Game>> {
+    let data = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&data)?)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReaderState {
+    Start,
+    Array { first: bool },
+    Stream,
+    Done,
+}
+
+/// Reads games one at a time from a JSON array of games or from a sequence
+/// of game objects, such as JSON Lines, without holding the whole input in
+/// memory. Reading stops after the first syntax error.
+pub struct JsonGameReader<R> {
+    reader: R,
+    state: ReaderState,
+}
+
+impl JsonGameReader<BufReader<File>> {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(JsonGameReader::new(BufReader::new(File::open(path)?)))
+    }
+}
+
+impl<R: BufRead> JsonGameReader<R> {
+    pub fn new(reader: R) -> Self {
+        JsonGameReader {
+            reader,
+            state: ReaderState::Start,
+        }
+    }
+
+    // skips whitespace and returns the next byte without consuming it
+    fn peek(&mut self) -> Result<Option<u8>> {
+        loop {
+            let buffer = self.reader.fill_buf()?;
+            let Some(&byte) = buffer.first() else {
+                return Ok(None);
+            };
+            if !byte.is_ascii_whitespace() {
+                return Ok(Some(byte));
+            }
+            self.reader.consume(1);
+        }
+    }
+
+    fn read_game(&mut self) -> Result<Option<Game>> {
+        loop {
+            match self.state {
+                ReaderState::Done => return Ok(None),
+                ReaderState::Start => {
+                    self.state = match self.peek()? {
+                        Some(b'[') => {
+                            self.reader.consume(1);
+                            ReaderState::Array { first: true }
+                        }
+                        Some(_) => ReaderState::Stream,
+                        None => ReaderState::Done,
+                    };
+                }
+                ReaderState::Array { first } => {
+                    match self.peek()? {
+                        Some(b']') => {
+                            self.state = ReaderState::Done;
+                            return Ok(None);
+                        }
+                        Some(b',') if !first => self.reader.consume(1),
+                        _ if first => {}
+                        _ => {
+                            return Err(serde_json::Error::custom(
+                                "expected `,` or `]` between games",
+                            )
+                            .into())
+                        }
+                    }
+                    self.state = ReaderState::Array { first: false };
+                    return self.deserialize().map(Some);
+                }
+                ReaderState::Stream => {
+                    return match self.peek()? {
+                        Some(_) => self.deserialize().map(Some),
+                        None => {
+                            self.state = ReaderState::Done;
+                            Ok(None)
+                        }
+                    };
+                }
+            }
+        }
+    }
+
+    fn deserialize(&mut self) -> Result<Game> {
+        let mut deserializer = serde_json::Deserializer::from_reader(&mut self.reader);
+        Ok(Game::deserialize(&mut deserializer)?)
+    }
+}
+
+impl<R: BufRead> Iterator for JsonGameReader<R> {
+    type Item = Result<Game>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.read_game() {
+            Ok(game) => game.map(Ok),
+            Err(e) => {
+                self.state = ReaderState::Done;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::game_json::{Game, JsonGameReader, MoveDetail, MoveKind};
+
+    const GAME: &str = r#"{"headers":[{"name":"Event","value":"E"}],"moves":[{"number":1,"black":{"kind":"simple","moves":[11,15]},"white":null}]}"#;
+
+    fn detail(kind: MoveKind, moves: &[u8]) -> MoveDetail {
+        MoveDetail {
+            kind,
+            moves: moves.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_into_move_errors() {
+        assert!(detail(MoveKind::Simple, &[9, 14]).into_move().is_ok());
+        assert!(detail(MoveKind::Jump, &[27, 18, 11]).into_move().is_ok());
+        assert!(matches!(
+            detail(MoveKind::Simple, &[9, 33]).into_move(),
+            Err(Error::InvalidSquare(33))
+        ));
+        assert!(matches!(
+            detail(MoveKind::Simple, &[9]).into_move(),
+            Err(Error::MalformedMove(s)) if s == "9"
+        ));
+        assert!(matches!(
+            detail(MoveKind::Jump, &[27, 23]).into_move(),
+            Err(Error::MalformedMove(s)) if s == "27x23"
+        ));
+        // 29 and 28 are a jump apart in bits, over the ghost bit between rows
+        assert!(matches!(
+            detail(MoveKind::Jump, &[29, 28]).into_move(),
+            Err(Error::MalformedMove(s)) if s == "29x28"
+        ));
+        for squares in [[11, 11], [1, 32], [9, 18]] {
+            assert!(matches!(
+                detail(MoveKind::Simple, &squares).into_move(),
+                Err(Error::MalformedMove(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_from_file_missing() {
+        assert!(matches!(
+            Game::from_file("tests/fixtures/does-not-exist.json"),
+            Err(Error::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_json_reader_formats() {
+        let array = format!("  [ {GAME},\n{GAME} ]\n");
+        let lines = format!("{GAME}\n{GAME}\n\n");
+        for input in [array, lines] {
+            let games = JsonGameReader::new(input.as_bytes())
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(games.len(), 2);
+            assert_eq!(games[1].header("Event"), Some("E"));
+        }
+        assert_eq!(JsonGameReader::new("[]".as_bytes()).count(), 0);
+        assert_eq!(JsonGameReader::new("".as_bytes()).count(), 0);
+    }
+
+    #[test]
+    fn test_json_reader_stops_after_error() {
+        let input = format!("[{GAME} {GAME}]");
+        let results: Vec<_> = JsonGameReader::new(input.as_bytes()).collect();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err(Error::Json(_))));
+    }
 }
@@ -0,0 +1,251 @@
+use crate::board::{Board, Color};
+use crate::fen::{parse_fen, FenError};
+use crate::move_generator::MoveGenerator;
+use crate::r#move::Move;
+use crate::zobrist;
+
+/// Plies without a capture or a man move after which the game is drawn by
+/// default: forty moves for each side.
+pub const DEFAULT_DRAW_PLIES: u32 = 80;
+
+/// Why the side to move lost.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LossReason {
+    NoPieces,
+    NoMoves,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawReason {
+    /// The same position with the same side to move occurred three times.
+    Repetition,
+    /// The no-capture, no-man-move limit was reached.
+    MoveLimit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameResult {
+    Win { winner: Color, reason: LossReason },
+    Draw(DrawReason),
+}
+
+/// A board together with the side to move and the history needed to detect
+/// the end of the game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameState {
+    board: Board,
+    side_to_move: Color,
+    ply: u32,
+    /// Plies since the last capture or man move.
+    quiet_plies: u32,
+    draw_plies: Option<u32>,
+    /// Zobrist key of every position reached, the current one last.
+    history: Vec<u64>,
+}
+
+impl Default for GameState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GameState {
+    pub fn new() -> Self {
+        GameState::from_position(Board::new(), Color::Black)
+    }
+
+    pub fn from_position(board: Board, side_to_move: Color) -> Self {
+        GameState {
+            board,
+            side_to_move,
+            ply: 0,
+            quiet_plies: 0,
+            draw_plies: Some(DEFAULT_DRAW_PLIES),
+            history: vec![zobrist::hash(&board, side_to_move)],
+        }
+    }
+
+    pub fn from_fen(fen: &str) -> Result<Self, FenError> {
+        let (board, color) = parse_fen(fen)?;
+        Ok(GameState::from_position(board, color))
+    }
+
+    /// Sets the number of plies without a capture or man move that draws the
+    /// game, or disables the rule with `None`.
+    pub fn with_draw_plies(mut self, draw_plies: Option<u32>) -> Self {
+        self.draw_plies = draw_plies;
+        self
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn side_to_move(&self) -> Color {
+        self.side_to_move
+    }
+
+    /// Plies played since the state was created.
+    pub fn ply(&self) -> u32 {
+        self.ply
+    }
+
+    pub fn quiet_plies(&self) -> u32 {
+        self.quiet_plies
+    }
+
+    pub fn key(&self) -> u64 {
+        *self
+            .history
+            .last()
+            .expect("history holds the current position")
+    }
+
+    pub fn legal_moves(&self) -> Vec<Move> {
+        MoveGenerator::new(self.board, self.side_to_move).generate_moves()
+    }
+
+    /// Plays `m` for the side to move, promoting any man that reaches the
+    /// last row. The move is not checked for legality.
+    pub fn play(&mut self, m: &Move) {
+        let color = self.side_to_move;
+        let man_moved = !m.movers.is_empty() && (m.movers & self.board.kings).is_empty();
+        let mut key = zobrist::update_move(self.key(), &self.board, color, m);
+        self.board.apply_move(color, m);
+        let new_kings = self.board.promote_kings();
+        key = zobrist::update_promotion(key, &self.board, new_kings);
+
+        self.side_to_move = color.opposite();
+        self.ply += 1;
+        if m.is_jump() || man_moved {
+            self.quiet_plies = 0;
+        } else {
+            self.quiet_plies += 1;
+        }
+        self.history.push(key);
+    }
+
+    /// Number of times the current position has occurred, counting itself.
+    pub fn repetitions(&self) -> usize {
+        let key = self.key();
+        // positions before the last capture or man move cannot recur
+        self.history
+            .iter()
+            .rev()
+            .take(self.quiet_plies as usize + 1)
+            .step_by(2)
+            .filter(|&&k| k == key)
+            .count()
+    }
+
+    /// The result of the game, or `None` while it is still in progress.
+    pub fn result(&self) -> Option<GameResult> {
+        let winner = self.side_to_move.opposite();
+        let pieces = match self.side_to_move {
+            Color::Black => self.board.bp,
+            Color::White => self.board.wp,
+        };
+        if pieces.is_empty() {
+            return Some(GameResult::Win {
+                winner,
+                reason: LossReason::NoPieces,
+            });
+        }
+        if !MoveGenerator::new(self.board, self.side_to_move).has_legal_move() {
+            return Some(GameResult::Win {
+                winner,
+                reason: LossReason::NoMoves,
+            });
+        }
+        if self.repetitions() >= 3 {
+            return Some(GameResult::Draw(DrawReason::Repetition));
+        }
+        if self
+            .draw_plies
+            .is_some_and(|limit| self.quiet_plies >= limit)
+        {
+            return Some(GameResult::Draw(DrawReason::MoveLimit));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::Color;
+    use crate::game_state::{DrawReason, GameResult, GameState, LossReason};
+    use crate::notation::parse_move;
+
+    fn play(state: &mut GameState, notation: &str) {
+        let m = parse_move(notation, state.board(), state.side_to_move()).unwrap();
+        state.play(&m);
+    }
+
+    #[test]
+    fn test_counters() {
+        let mut state = GameState::from_fen("B:WK28:BK1,12").unwrap();
+        play(&mut state, "1-5");
+        assert_eq!((state.ply(), state.quiet_plies()), (1, 1));
+        assert_eq!(state.side_to_move(), Color::White);
+        play(&mut state, "28-24");
+        play(&mut state, "12-16");
+        assert_eq!((state.ply(), state.quiet_plies()), (3, 0));
+        assert_eq!(state.result(), None);
+    }
+
+    #[test]
+    fn test_win() {
+        let mut state = GameState::from_fen("W:W23:B18").unwrap();
+        play(&mut state, "23x14");
+        assert_eq!(
+            state.result(),
+            Some(GameResult::Win {
+                winner: Color::White,
+                reason: LossReason::NoPieces
+            })
+        );
+
+        // the black man on 28 cannot move past the edge
+        let state = GameState::from_fen("B:W32:B28").unwrap();
+        assert_eq!(
+            state.result(),
+            Some(GameResult::Win {
+                winner: Color::White,
+                reason: LossReason::NoMoves
+            })
+        );
+    }
+
+    #[test]
+    fn test_repetition() {
+        let mut state = GameState::from_fen("B:WK32:BK1").unwrap();
+        for _ in 0..2 {
+            assert_eq!(state.result(), None);
+            play(&mut state, "1-5");
+            play(&mut state, "32-28");
+            play(&mut state, "5-1");
+            play(&mut state, "28-32");
+        }
+        assert_eq!(state.repetitions(), 3);
+        assert_eq!(
+            state.result(),
+            Some(GameResult::Draw(DrawReason::Repetition))
+        );
+    }
+
+    #[test]
+    fn test_move_limit() {
+        let mut state = GameState::from_fen("B:WK32:BK1")
+            .unwrap()
+            .with_draw_plies(Some(4));
+        for notation in ["1-5", "32-27", "5-9", "27-23"] {
+            assert_eq!(state.result(), None);
+            play(&mut state, notation);
+        }
+        assert_eq!(
+            state.result(),
+            Some(GameResult::Draw(DrawReason::MoveLimit))
+        );
+        assert_eq!(state.clone().with_draw_plies(None).result(), None);
+    }
+}
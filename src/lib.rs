@@ -1,7 +1,19 @@
+pub mod archive;
 pub mod bitboard;
 pub mod board;
+pub mod diagram;
+pub mod error;
+pub mod fen;
 pub mod game_json;
+pub mod game_state;
 pub mod r#move;
 pub mod move_generator;
+pub mod move_list;
+pub mod notation;
+pub mod pdn;
 pub mod perft;
+pub mod replay;
+pub mod square;
 pub mod translate;
+pub mod transposition;
+pub mod zobrist;
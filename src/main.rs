@@ -1,23 +1,468 @@
-use checkers_perft::board::{Board, Color};
-use clap::Parser;
+use checkers_perft::{
+    archive::{validate_games, GameFailure, GameReader},
+    board::{Board, Color},
+    fen::{parse_fen, to_fen, INITIAL_FEN},
+    perft::{self, DivideEntry, PerftOptions, PlyStats},
+    replay::ReplayError,
+    transposition::TranspositionTable,
+};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Instant,
+};
 
 /// Run perft for checkers move generation
 #[derive(Parser, Debug)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Depth of the search
-    #[arg(short, long)]
+    #[arg(short, long, required = true)]
+    depth: Option<u32>,
+
+    /// Print the node count below each root move
+    #[arg(long)]
+    divide: bool,
+
+    /// Print captures, promotions and game overs for every ply, searching
+    /// single-threaded without hashing or bulk counting
+    #[arg(
+        long,
+        conflicts_with_all = ["divide", "hash", "bulk", "make_unmake", "threads", "split_depth"]
+    )]
+    stats: bool,
+
+    /// Also count nodes with capture routes that reach the same position
+    /// merged into one move, searching single-threaded without hashing or
+    /// bulk counting
+    #[arg(
+        long,
+        conflicts_with_all = ["divide", "stats", "hash", "bulk", "make_unmake", "threads", "split_depth"]
+    )]
+    positions: bool,
+
+    /// Run every depth from 1 up to the given depth, with timings
+    #[arg(long, conflicts_with_all = ["divide", "stats", "positions"])]
+    iterative: bool,
+
+    /// Compare iterative results with known counts for the initial position
+    #[arg(long, requires = "iterative")]
+    reference: bool,
+
+    /// Position to search, as a PDN FEN string
+    #[arg(long, default_value = INITIAL_FEN)]
+    fen: String,
+
+    /// Count the last ply from the number of generated moves
+    #[arg(long)]
+    bulk: bool,
+
+    /// Backtrack with make/unmake instead of copying the board at every node
+    #[arg(long)]
+    make_unmake: bool,
+
+    /// Transposition table size in MB (0 disables hashing)
+    #[arg(long, default_value_t = 0)]
+    hash: usize,
+
+    /// Number of worker threads (defaults to one per CPU)
+    #[arg(long)]
+    threads: Option<usize>,
+
+    /// Number of plies searched in parallel, or "auto" to split until every
+    /// thread has enough work
+    #[arg(long, default_value = "auto")]
+    split_depth: SplitDepth,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Check that every move of the games in each file is legal, reading
+    /// games one at a time and validating them in parallel
+    Validate {
+        /// Game files, as PDN (`.pdn`), a JSON array, or one JSON game per line
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SplitDepth(Option<u32>);
+
+impl FromStr for SplitDepth {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "auto" {
+            return Ok(SplitDepth(None));
+        }
+        s.parse()
+            .map(|plies| SplitDepth(Some(plies)))
+            .map_err(|_| format!("expected a number of plies or \"auto\", found {s:?}"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Serialize)]
+struct Iteration {
+    depth: u32,
+    nodes: u64,
+    elapsed_seconds: f64,
+    nodes_per_second: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reference: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+struct Report {
+    fen: String,
+    side_to_move: &'static str,
     depth: u32,
+    threads: usize,
+    nodes: u64,
+    elapsed_seconds: f64,
+    nodes_per_second: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    position_nodes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    divide: Option<Vec<DivideEntry>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stats: Option<Vec<PlyStats>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    iterations: Option<Vec<Iteration>>,
+}
+
+impl Report {
+    fn new(board: &Board, color: Color, depth: u32) -> Self {
+        Report {
+            fen: to_fen(board, color),
+            side_to_move: match color {
+                Color::Black => "black",
+                Color::White => "white",
+            },
+            depth,
+            threads: rayon::current_num_threads(),
+            nodes: 0,
+            elapsed_seconds: 0.0,
+            nodes_per_second: 0.0,
+            position_nodes: None,
+            divide: None,
+            stats: None,
+            iterations: None,
+        }
+    }
+
+    fn timed(mut self, nodes: u64, start: Instant) -> Self {
+        self.nodes = nodes;
+        self.elapsed_seconds = start.elapsed().as_secs_f64();
+        self.nodes_per_second = nodes_per_second(nodes, self.elapsed_seconds);
+        self
+    }
+
+    fn print_text(&self) {
+        if let Some(stats) = &self.stats {
+            println!(
+                "{:>5} {:>16} {:>14} {:>14} {:>14} {:>12} {:>12}",
+                "ply", "nodes", "captures", "multi", "captured", "promotions", "game overs"
+            );
+            for (ply, s) in stats.iter().enumerate() {
+                println!(
+                    "{:>5} {:>16} {:>14} {:>14} {:>14} {:>12} {:>12}",
+                    ply,
+                    s.nodes,
+                    s.captures,
+                    s.multi_captures,
+                    s.pieces_captured,
+                    s.promotions,
+                    s.game_overs
+                );
+            }
+        } else if let Some(iterations) = &self.iterations {
+            let compare = iterations.iter().any(|i| i.reference.is_some());
+            println!(
+                "{:>5} {:>20} {:>10} {:>14}{}",
+                "depth",
+                "nodes",
+                "time (s)",
+                "nodes/s",
+                if compare { "  reference" } else { "" }
+            );
+            for i in iterations {
+                let reference = match i.reference {
+                    _ if !compare => String::new(),
+                    Some(expected) if expected == i.nodes => "  ok".to_string(),
+                    Some(expected) => format!("  MISMATCH (expected {expected})"),
+                    None => "  unknown".to_string(),
+                };
+                println!(
+                    "{:>5} {:>20} {:>10.3} {:>14.0}{}",
+                    i.depth, i.nodes, i.elapsed_seconds, i.nodes_per_second, reference
+                );
+            }
+        } else if let Some(entries) = &self.divide {
+            for entry in entries {
+                println!("{}: {}", entry.notation, entry.nodes);
+            }
+            println!();
+            println!(
+                "moves = {}, perft({}) = {}",
+                entries.len(),
+                self.depth,
+                self.nodes
+            );
+        } else {
+            println!("perft({}) = {}", self.depth, self.nodes);
+            if let Some(positions) = self.position_nodes {
+                println!("position distinct perft({}) = {}", self.depth, positions);
+            }
+        }
+    }
+}
+
+fn nodes_per_second(nodes: u64, seconds: f64) -> f64 {
+    nodes as f64 / seconds.max(f64::EPSILON)
+}
+
+#[derive(Debug, Serialize)]
+struct GameReport {
+    /// Position of the game in its file, counting from 1.
+    game: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    event: Option<String>,
+    error: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    move_number: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    color: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fen: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    candidates: Option<Vec<String>>,
+}
+
+impl From<GameFailure> for GameReport {
+    fn from(failure: GameFailure) -> Self {
+        let report = GameReport {
+            game: failure.index,
+            event: failure.event,
+            error: failure.error.to_string(),
+            move_number: None,
+            color: None,
+            fen: None,
+            candidates: None,
+        };
+        match failure.error {
+            ReplayError::Illegal(illegal) => GameReport {
+                move_number: Some(illegal.number),
+                color: Some(color_name(illegal.color)),
+                fen: Some(to_fen(&illegal.board, illegal.color)),
+                candidates: Some(illegal.candidates),
+                ..report
+            },
+            ReplayError::Invalid(_) => report,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct FileReport {
+    file: String,
+    games: usize,
+    passed: usize,
+    failed: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    failures: Vec<GameReport>,
+}
+
+fn color_name(color: Color) -> &'static str {
+    match color {
+        Color::Black => "black",
+        Color::White => "white",
+    }
+}
+
+fn validate_file(path: &Path) -> FileReport {
+    let mut report = FileReport {
+        file: path.display().to_string(),
+        games: 0,
+        passed: 0,
+        failed: 0,
+        error: None,
+        failures: Vec::new(),
+    };
+    match GameReader::open(path) {
+        Ok(games) => {
+            let summary = validate_games(games);
+            report.games = summary.games;
+            report.passed = summary.passed;
+            report.failed = summary.failures.len();
+            report.failures = summary.failures.into_iter().map(GameReport::from).collect();
+        }
+        Err(e) => report.error = Some(e.to_string()),
+    }
+    report
+}
+
+fn run_validate(files: &[PathBuf], format: Format) {
+    let reports: Vec<FileReport> = files.iter().map(|path| validate_file(path)).collect();
+
+    match format {
+        Format::Text => {
+            for file in &reports {
+                if let Some(error) = &file.error {
+                    println!("{}: {}", file.file, error);
+                }
+                for r in &file.failures {
+                    println!("{} game {}: {}", file.file, r.game, r.error);
+                    if let Some(fen) = &r.fen {
+                        println!("  position: {fen}");
+                    }
+                }
+            }
+            let games: usize = reports.iter().map(|f| f.games).sum();
+            let passed: usize = reports.iter().map(|f| f.passed).sum();
+            println!("{games} games, {passed} passed, {} failed", games - passed);
+        }
+        Format::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&reports).expect("report serializes to JSON")
+        ),
+    }
+
+    if reports.iter().any(|f| f.error.is_some()) {
+        std::process::exit(2);
+    }
+    if reports.iter().any(|f| f.failed > 0) {
+        std::process::exit(1);
+    }
 }
 
 fn main() {
     let args = Args::parse();
 
-    let board = Board::new();
-    let color = Color::Black;
+    if let Some(Command::Validate { files, format }) = &args.command {
+        run_validate(files, *format);
+        return;
+    }
+    let depth = args
+        .depth
+        .expect("clap requires --depth without a subcommand");
+
+    if args.divide && depth == 0 {
+        eprintln!("--divide needs a depth of at least 1");
+        std::process::exit(2);
+    }
+
+    let (board, color) = match parse_fen(&args.fen) {
+        Ok(position) => position,
+        Err(e) => {
+            eprintln!("invalid FEN {:?}: {}", args.fen, e);
+            std::process::exit(2);
+        }
+    };
+
+    if let Some(threads) = args.threads {
+        if let Err(e) = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()
+        {
+            eprintln!("unable to start {threads} threads: {e}");
+            std::process::exit(2);
+        }
+    }
+
+    let tt = (args.hash > 0).then(|| TranspositionTable::new(args.hash));
+    let options = PerftOptions {
+        bulk: args.bulk,
+        split_depth: args.split_depth.0,
+        make_unmake: args.make_unmake,
+    };
+    let count = |depth| perft::perft_with(color, &board, depth, &options, tt.as_ref());
+
+    let report = Report::new(&board, color, depth);
+    let start = Instant::now();
+    let report = if args.stats {
+        let stats = perft::perft_stats(color, &board, depth);
+        Report {
+            stats: Some(stats.plies.clone()),
+            ..report.timed(stats.nodes(), start)
+        }
+    } else if args.positions {
+        let counts = perft::perft_counts(color, &board, depth);
+        Report {
+            position_nodes: Some(counts.positions),
+            ..report.timed(counts.paths, start)
+        }
+    } else if args.iterative {
+        if args.reference && (board, color) != parse_fen(INITIAL_FEN).unwrap() {
+            eprintln!("--reference is only available for the initial position");
+            std::process::exit(2);
+        }
+        let mut iterations = Vec::new();
+        for depth in 1..=depth {
+            let start = Instant::now();
+            let nodes = count(depth);
+            let elapsed_seconds = start.elapsed().as_secs_f64();
+            iterations.push(Iteration {
+                depth,
+                nodes,
+                elapsed_seconds,
+                nodes_per_second: nodes_per_second(nodes, elapsed_seconds),
+                reference: perft::INITIAL_POSITION_PERFT
+                    .get(depth as usize)
+                    .copied()
+                    .filter(|_| args.reference),
+            });
+        }
+        let nodes = iterations.last().map_or(1, |i| i.nodes);
+        Report {
+            iterations: Some(iterations),
+            ..report.timed(nodes, start)
+        }
+    } else if args.divide {
+        let entries = perft::divide_with(color, &board, depth, &options, tt.as_ref());
+        let nodes = entries.iter().map(|e| e.nodes).sum();
+        Report {
+            divide: Some(entries),
+            ..report.timed(nodes, start)
+        }
+    } else {
+        report.timed(count(depth), start)
+    };
+
+    match args.format {
+        Format::Text => report.print_text(),
+        Format::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&report).expect("report serializes to JSON")
+        ),
+    }
 
-    println!(
-        "perft({}) = {}",
-        args.depth,
-        checkers_perft::perft::perft(color, &board, args.depth)
-    );
+    let mismatch = report
+        .iterations
+        .iter()
+        .flatten()
+        .any(|i| i.reference.is_some_and(|expected| expected != i.nodes));
+    if mismatch {
+        std::process::exit(1);
+    }
 }
@@ -1,24 +1,279 @@
 use crate::bitboard::Bitboard;
+use crate::board::{Board, Color};
+use crate::error::{Error, Result};
+use crate::square::Square;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// One landing square per capturable piece.
+pub const MAX_PATH: usize = 12;
+
+const SQUARE_BITS: u32 = 5;
+const SQUARE_MASK: u64 = (1 << SQUARE_BITS) - 1;
+const LEN_SHIFT: u32 = 60;
+
+/// Ordered landing squares of a move. Squares are packed five bits each,
+/// with the length in the top four bits, so moves stay small and `Copy`.
+/// An empty path means the route was not recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Path(u64);
+
+// zero-based square number of a single playable bit; anything else is a
+// caller bug, packed as square 1 in release builds rather than spilling
+// into the neighbouring fields
+#[inline]
+fn square_index(square: Bitboard) -> u64 {
+    let bit = u64::from(square).trailing_zeros() as u8;
+    let square = Square::from_bit_index(bit);
+    debug_assert!(square.is_some(), "bit {bit} is not a playable square");
+    square.map_or(0, |s| u64::from(s.number() - 1))
+}
+
+impl Path {
+    pub const fn new() -> Self {
+        Path(0)
+    }
+
+    /// Builds a path from single-square bitboards, rejecting bits that are
+    /// not playable squares and paths longer than [`MAX_PATH`].
+    pub fn from_bitboards(squares: &[Bitboard]) -> Result<Self> {
+        if squares.len() > MAX_PATH {
+            return Err(Error::MalformedMove(format!(
+                "{} landing squares",
+                squares.len()
+            )));
+        }
+        let mut path = Path::new();
+        for &square in squares {
+            let bit = u64::from(square).trailing_zeros() as u8;
+            if square.len() != 1 || Square::from_bit_index(bit).is_none() {
+                return Err(Error::InvalidBitIndex(bit));
+            }
+            path.push(square);
+        }
+        Ok(path)
+    }
+
+    pub fn try_from_notation_vector(notation: &[u8]) -> Result<Self> {
+        let squares = notation
+            .iter()
+            .map(|&n| Bitboard::try_from_notation_vector(&[n]))
+            .collect::<Result<Vec<_>>>()?;
+        Path::from_bitboards(&squares)
+    }
+
+    /// Appends `square`, which must be a single playable square and fit
+    /// within [`MAX_PATH`]; use [`from_bitboards`](Self::from_bitboards)
+    /// for unchecked input.
+    #[inline]
+    pub fn push(&mut self, square: Bitboard) {
+        let len = self.len();
+        debug_assert!(len < MAX_PATH);
+        let index = square_index(square);
+        self.0 = (self.0 & !(0xf << LEN_SHIFT))
+            | index << (len as u32 * SQUARE_BITS)
+            | ((len as u64 + 1) << LEN_SHIFT);
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        (self.0 >> LEN_SHIFT) as usize
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Square number of the `i`th landing square.
+    #[inline]
+    pub fn square(&self, i: usize) -> u8 {
+        ((self.0 >> (i as u32 * SQUARE_BITS)) & SQUARE_MASK) as u8 + 1
+    }
+
+    pub fn last(&self) -> Option<u8> {
+        self.len().checked_sub(1).map(|i| self.square(i))
+    }
+
+    pub fn as_notation_vector(&self) -> Vec<u8> {
+        (0..self.len()).map(|i| self.square(i)).collect()
+    }
+}
+
+/// A move as the pieces it moves and captures. Equality and hashing ignore
+/// `path`, so a move built without a route matches every generated route
+/// that leads to the same position; use [`Move::same_route`] to compare
+/// routes as well.
+#[derive(Debug, Clone, Copy)]
 pub struct Move {
+    /// The square the piece leaves and the square it lands on. A capture
+    /// that ends back on its starting square moves no piece, so it leaves
+    /// this empty.
     pub movers: Bitboard,
     pub jumped: Bitboard,
+    pub path: Path,
 }
 
 impl Move {
-    pub fn new(movers: Bitboard, jumped: Bitboard) -> Self {
-        Move { movers, jumped }
+    pub const fn new(movers: Bitboard, jumped: Bitboard) -> Self {
+        Move {
+            movers,
+            jumped,
+            path: Path::new(),
+        }
+    }
+
+    #[inline]
+    pub fn with_path(movers: Bitboard, jumped: Bitboard, path: Path) -> Self {
+        Move {
+            movers,
+            jumped,
+            path,
+        }
+    }
+
+    /// Whether both moves lead to the same position by the same route.
+    #[inline]
+    pub fn same_route(&self, other: &Move) -> bool {
+        self == other && self.path == other.path
+    }
+
+    #[inline]
+    pub fn is_jump(&self) -> bool {
+        !self.jumped.is_empty()
+    }
+
+    /// Starting square of a move with a recorded path: the last landing
+    /// square when `movers` is empty.
+    pub fn origin(&self) -> Option<u8> {
+        let last = self.path.last()?;
+        if self.movers.is_empty() {
+            return Some(last);
+        }
+        let destination = Square::new(last)?.bitboard();
+        (self.movers ^ destination)
+            .squares()
+            .next()
+            .map(Square::number)
+    }
+
+    #[inline]
+    fn separator(&self) -> &'static str {
+        if self.is_jump() {
+            "x"
+        } else {
+            "-"
+        }
+    }
+
+    /// Standard notation of a move with a recorded path, listing every
+    /// landing square (`11-15`, `9x18x27`).
+    pub fn path_notation(&self) -> Option<String> {
+        let mut notation = self.origin()?.to_string();
+        for n in self.path.as_notation_vector() {
+            notation.push_str(self.separator());
+            notation.push_str(&n.to_string());
+        }
+        Some(notation)
+    }
+
+    /// Renders the move in standard checkers notation (`11-15`, `22x15`) as
+    /// played by `color` on `board`, the position before the move is applied.
+    /// Moves with a recorded path list every landing square (`9x18x27`).
+    pub fn to_notation(&self, board: &Board, color: Color) -> String {
+        let separator = self.separator();
+        if let Some(notation) = self.path_notation() {
+            return notation;
+        }
+
+        let own = match color {
+            Color::Black => board.bp,
+            Color::White => board.wp,
+        };
+        let mut origin = self.movers & own;
+        let mut destination = self.movers ^ origin;
+        if origin.is_empty() {
+            // find the king that sits next to the captured pieces
+            let neighbours =
+                self.jumped << 4 | self.jumped >> 4 | self.jumped << 5 | self.jumped >> 5;
+            let candidates = neighbours & own & board.kings;
+            origin = candidates
+                .into_iter()
+                .next()
+                .map_or(candidates, Bitboard::from);
+            destination = origin;
+        }
+        let number = |squares: Bitboard| squares.squares().next().map_or(0, Square::number);
+        format!("{}{}{}", number(origin), separator, number(destination))
     }
 }
+
+impl PartialEq for Move {
+    fn eq(&self, other: &Move) -> bool {
+        self.movers == other.movers && self.jumped == other.jumped
+    }
+}
+
+impl Eq for Move {}
+
+impl Hash for Move {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        u64::from(self.movers).hash(state);
+        u64::from(self.jumped).hash(state);
+    }
+}
+
 impl fmt::Display for Move {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "Move {{ movers: {:?}, jumped: {:?} }}",
-            self.movers.as_notation_vector(),
-            self.jumped.as_notation_vector()
-        )
+        match self.path_notation() {
+            Some(notation) => write!(f, "{notation}"),
+            None => write!(
+                f,
+                "Move {{ movers: {:?}, jumped: {:?} }}",
+                self.movers
+                    .squares()
+                    .map(Square::number)
+                    .collect::<Vec<_>>(),
+                self.jumped
+                    .squares()
+                    .map(Square::number)
+                    .collect::<Vec<_>>()
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bitboard::Bitboard,
+        error::Error,
+        r#move::{Path, MAX_PATH},
+    };
+
+    #[test]
+    fn test_path_from_bitboards() {
+        let squares: Vec<Bitboard> = [18, 11, 4]
+            .iter()
+            .map(|&n| Bitboard::try_from_notation_vector(&[n]).unwrap())
+            .collect();
+        let path = Path::from_bitboards(&squares).unwrap();
+        assert_eq!(path.as_notation_vector(), vec![18, 11, 4]);
+
+        // ghost bits, empty and multi-square bitboards are not squares
+        for square in [
+            Bitboard::from(1 << 9),
+            Bitboard::new(),
+            squares[0] | squares[1],
+        ] {
+            assert!(matches!(
+                Path::from_bitboards(&[square]),
+                Err(Error::InvalidBitIndex(_))
+            ));
+        }
+        assert!(matches!(
+            Path::from_bitboards(&[squares[0]; MAX_PATH + 1]),
+            Err(Error::MalformedMove(_))
+        ));
     }
 }
@@ -1,28 +1,119 @@
 use crate::bitboard::Bitboard;
 use crate::board::{Board, Color};
-use crate::r#move::Move;
+use crate::move_list::MoveList;
+use crate::r#move::{Move, Path};
+
+/// How capture sequences that end in the same position are reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CaptureMode {
+    /// Every distinct capture route is a separate move.
+    #[default]
+    PathDistinct,
+    /// Routes that capture the same pieces and end on the same square are
+    /// reported once.
+    PositionDistinct,
+}
 
 pub struct MoveGenerator {
     board: Board,
     color: Color,
+    capture_mode: CaptureMode,
 }
 
 impl MoveGenerator {
     pub fn new(board: Board, color: Color) -> Self {
-        MoveGenerator { board, color }
+        MoveGenerator {
+            board,
+            color,
+            capture_mode: CaptureMode::default(),
+        }
+    }
+
+    pub fn with_capture_mode(mut self, capture_mode: CaptureMode) -> Self {
+        self.capture_mode = capture_mode;
+        self
     }
 
+    /// All legal moves, see [`fill_moves`](Self::fill_moves).
     pub fn generate_moves(&self) -> Vec<Move> {
+        let mut moves = MoveList::new();
+        self.fill_moves(&mut moves);
+        moves.to_vec()
+    }
+
+    /// Replaces the contents of `moves` with every legal move, without
+    /// allocating.
+    pub fn fill_moves(&self, moves: &mut MoveList) {
+        self.fill_captures(moves);
+        if moves.is_empty() {
+            self.fill_simple_moves(moves);
+        }
+    }
+
+    /// Captures available to the side to move, or nothing if it has none.
+    /// Since capturing is compulsory these are its legal moves whenever this
+    /// is not empty.
+    pub fn generate_captures(&self) -> Vec<Move> {
+        let mut moves = MoveList::new();
+        self.fill_captures(&mut moves);
+        moves.to_vec()
+    }
+
+    /// Replaces the contents of `moves` with the available captures.
+    pub fn fill_captures(&self, moves: &mut MoveList) {
+        moves.clear();
+        let jumpers = self.board.jumpers(self.color);
+        if !jumpers.is_empty() {
+            self.fill_jump_moves(moves, jumpers);
+            if self.capture_mode == CaptureMode::PositionDistinct {
+                dedup_positions(moves);
+            }
+        }
+    }
+
+    /// Legal moves that capture nothing. Empty when a capture is available,
+    /// as capturing is then compulsory.
+    pub fn generate_quiet_moves(&self) -> Vec<Move> {
+        let mut moves = MoveList::new();
+        self.fill_quiet_moves(&mut moves);
+        moves.to_vec()
+    }
+
+    /// Replaces the contents of `moves` with the legal non-capturing moves.
+    pub fn fill_quiet_moves(&self, moves: &mut MoveList) {
+        moves.clear();
+        if !self.has_capture() {
+            self.fill_simple_moves(moves);
+        }
+    }
+
+    /// Whether the side to move can capture, without generating any move.
+    pub fn has_capture(&self) -> bool {
+        !self.board.jumpers(self.color).is_empty()
+    }
+
+    /// Whether the side to move has any legal move, without generating any.
+    pub fn has_legal_move(&self) -> bool {
+        self.has_capture() || !self.board.movers(self.color).is_empty()
+    }
+
+    /// Number of moves [`generate_moves`](Self::generate_moves) would return,
+    /// computed without building the move list when captures are path
+    /// distinct.
+    pub fn count_moves(&self) -> u64 {
         let jumpers = self.board.jumpers(self.color);
         if jumpers.is_empty() {
-            self.generate_simple_moves()
+            self.count_simple_moves()
+        } else if self.capture_mode == CaptureMode::PositionDistinct {
+            let mut moves = MoveList::new();
+            self.fill_moves(&mut moves);
+            moves.len() as u64
         } else {
-            self.generate_jump_moves(jumpers)
+            self.count_jump_moves(jumpers)
         }
     }
 
-    fn generate_simple_moves(&self) -> Vec<Move> {
-        let mut moves = Vec::with_capacity(10);
+    fn fill_simple_moves(&self, moves: &mut MoveList) {
         let movers = self.board.movers(self.color);
         for mover in movers {
             let mover_bb = Bitboard::from(mover);
@@ -30,14 +121,75 @@ impl MoveGenerator {
             for possible_move in possible_moves {
                 let jumped = Bitboard::new();
                 let possible_move_bb = Bitboard::from(possible_move);
-                moves.push(Move::new(mover_bb | possible_move_bb, jumped));
+                let mut path = Path::new();
+                path.push(possible_move_bb);
+                moves.push(Move::with_path(mover_bb | possible_move_bb, jumped, path));
             }
         }
-        moves
     }
 
-    fn generate_jump_moves(&self, jumpers: Bitboard) -> Vec<Move> {
-        let mut jumps = Vec::with_capacity(7);
+    fn fill_jump_moves(&self, moves: &mut MoveList, jumpers: Bitboard) {
+        for jumper in jumpers {
+            let jumper_bb = Bitboard::from(jumper);
+            self.fill_jumps_from(
+                moves,
+                &self.board,
+                jumper_bb,
+                jumper_bb,
+                Bitboard::new(),
+                Path::new(),
+            );
+        }
+    }
+
+    // extends the capture that started on `origin`, has reached `square` on
+    // `board` and has taken `jumped` so far, pushing every completed route
+    fn fill_jumps_from(
+        &self,
+        moves: &mut MoveList,
+        board: &Board,
+        origin: Bitboard,
+        square: Bitboard,
+        jumped: Bitboard,
+        path: Path,
+    ) {
+        for destination in board.jumps(self.color, square) {
+            let destination_bb = Bitboard::from(destination);
+            let jumped_bb = square.jumped(destination_bb);
+            let mut new_board = *board;
+            new_board.apply_move(self.color, &Move::new(square | destination_bb, jumped_bb));
+
+            let mut new_path = path;
+            new_path.push(destination_bb);
+            if new_board.jumps(self.color, destination_bb).is_empty() {
+                moves.push(Move::with_path(
+                    origin ^ destination_bb,
+                    jumped | jumped_bb,
+                    new_path,
+                ));
+            } else {
+                self.fill_jumps_from(
+                    moves,
+                    &new_board,
+                    origin,
+                    destination_bb,
+                    jumped | jumped_bb,
+                    new_path,
+                );
+            }
+        }
+    }
+
+    fn count_simple_moves(&self) -> u64 {
+        let mut count = 0;
+        for mover in self.board.movers(self.color) {
+            count += self.board.moves(self.color, Bitboard::from(mover)).len() as u64;
+        }
+        count
+    }
+
+    fn count_jump_moves(&self, jumpers: Bitboard) -> u64 {
+        let mut count = 0;
         for jumper in jumpers {
             let jumper_bb = Bitboard::from(jumper);
             let possible_jumps = self.board.jumps(self.color, jumper_bb);
@@ -49,72 +201,101 @@ impl MoveGenerator {
                 let mut new_board = self.board;
                 new_board.apply_move(self.color, &current_move);
 
-                let multiple_jumps = new_board.jumps(self.color, destination_bb);
-
-                if multiple_jumps.is_empty() {
-                    jumps.push(current_move);
+                if new_board.jumps(self.color, destination_bb).is_empty() {
+                    count += 1;
                 } else {
                     let move_generator = MoveGenerator::new(new_board, self.color);
-                    let further_jumps = move_generator.generate_jump_moves(destination_bb);
-                    for further_jump in further_jumps {
-                        let combined_move = Move::new(
-                            current_move.movers ^ further_jump.movers,
-                            current_move.jumped | further_jump.jumped,
-                        );
-                        jumps.push(combined_move);
-                    }
+                    count += move_generator.count_jump_moves(destination_bb);
                 }
             }
         }
-        jumps
+        count
+    }
+}
+
+fn dedup_positions(moves: &mut MoveList) {
+    let mut i = 0;
+    while i < moves.len() {
+        if moves[..i].contains(&moves[i]) {
+            moves.remove(i);
+        } else {
+            i += 1;
+        }
     }
 }
+
 #[cfg(test)]
 mod tests {
     use crate::{
         bitboard::Bitboard,
         board::{Board, Color},
-        move_generator::MoveGenerator,
-        r#move::Move,
+        diagram::parse_diagram,
+        move_generator::{CaptureMode, MoveGenerator},
+        move_list::{MoveList, MAX_MOVES},
+        r#move::{Move, Path},
     };
 
     #[test]
     fn test_movegen_multi_jump() {
-        let bp = Bitboard::from_notation_vector(&[23, 15, 8]);
-        let wp = Bitboard::from_notation_vector(&[27]);
+        let bp = Bitboard::try_from_notation_vector(&[23, 15, 8]).unwrap();
+        let wp = Bitboard::try_from_notation_vector(&[27]).unwrap();
         let kings = Bitboard::from(0);
         let board = Board::from_bitboards(bp, wp, kings);
         let moves = MoveGenerator::new(board, Color::White).generate_moves();
         assert!(moves.len() == 1);
         let m = Move::new(
-            Bitboard::from_notation_vector(&[27, 4]),
-            Bitboard::from_notation_vector(&[23, 15, 8]),
+            Bitboard::try_from_notation_vector(&[27, 4]).unwrap(),
+            Bitboard::try_from_notation_vector(&[23, 15, 8]).unwrap(),
         );
         assert!(moves.contains(&m));
+        let route = Move::with_path(
+            m.movers,
+            m.jumped,
+            Path::try_from_notation_vector(&[18, 11, 4]).unwrap(),
+        );
+        assert!(moves[0].same_route(&route));
     }
 
     #[test]
     fn test_movegen_circle_jump() {
-        let board = Board::from_bitboards(
-            Bitboard::from_notation_vector(&[23]),
-            Bitboard::from_notation_vector(&[18, 19, 10, 11]),
-            Bitboard::from_notation_vector(&[23]),
-        );
+        let board = parse_diagram(
+            "
+            ........
+            ........
+            ...w.w..
+            ........
+            ...w.w..
+            ....B...
+            ........
+            ........
+            ",
+        )
+        .unwrap();
         let moves = MoveGenerator::new(board, Color::Black).generate_moves();
         assert!(moves.len() == 2);
         let m = Move::new(
             Bitboard::new(),
-            Bitboard::from_notation_vector(&[18, 19, 10, 11]),
+            Bitboard::try_from_notation_vector(&[18, 19, 10, 11]).unwrap(),
         );
         assert!(moves.contains(&m));
+        assert!(moves.iter().all(|g| *g == m));
+        let mut paths: Vec<Vec<u8>> = moves.iter().map(|g| g.path.as_notation_vector()).collect();
+        paths.sort();
+        assert_eq!(paths, vec![vec![14, 7, 16, 23], vec![16, 7, 14, 23]]);
+
+        let moves = MoveGenerator::new(board, Color::Black)
+            .with_capture_mode(CaptureMode::PositionDistinct)
+            .generate_moves();
+        assert!(moves.len() == 1);
+        assert!(moves.contains(&m));
     }
 
     #[test]
     fn test_movegen_black_kings() {
         let board = Board::from_bitboards(
-            Bitboard::from_notation_vector(&[29]),
+            Bitboard::try_from_notation_vector(&[29]).unwrap(),
             Bitboard::new(),
-            Bitboard::from_notation_vector(&[29]),
+            Bitboard::try_from_notation_vector(&[29]).unwrap(),
         );
         let moves = MoveGenerator::new(board, Color::Black).generate_moves();
         assert!(
@@ -122,7 +303,10 @@ mod tests {
             "expected 1 move, found: {:?}",
             moves.len()
         );
-        let m = Move::new(Bitboard::from_notation_vector(&[29, 25]), Bitboard::new());
+        let m = Move::new(
+            Bitboard::try_from_notation_vector(&[29, 25]).unwrap(),
+            Bitboard::new(),
+        );
         assert!(
             moves.contains(&m),
             "expected: {:?} in moves: {:?}",
@@ -130,4 +314,92 @@ mod tests {
             moves
         );
     }
+
+    #[test]
+    fn test_captures_and_quiet_moves() {
+        let board = Board::new();
+        let generator = MoveGenerator::new(board, Color::Black);
+        assert!(generator.has_legal_move());
+        assert!(!generator.has_capture());
+        assert!(generator.generate_captures().is_empty());
+        assert_eq!(generator.generate_quiet_moves(), generator.generate_moves());
+
+        // white on 27 must capture 23, 15 and 8
+        let board = Board::from_bitboards(
+            Bitboard::try_from_notation_vector(&[23, 15, 8]).unwrap(),
+            Bitboard::try_from_notation_vector(&[27]).unwrap(),
+            Bitboard::new(),
+        );
+        let generator = MoveGenerator::new(board, Color::White);
+        assert!(generator.has_capture());
+        assert!(generator.generate_quiet_moves().is_empty());
+        assert_eq!(generator.generate_captures(), generator.generate_moves());
+
+        let blocked = Board::from_bitboards(
+            Bitboard::try_from_notation_vector(&[28]).unwrap(),
+            Bitboard::try_from_notation_vector(&[32]).unwrap(),
+            Bitboard::new(),
+        );
+        let generator = MoveGenerator::new(blocked, Color::Black);
+        assert!(!generator.has_legal_move());
+        assert!(!generator.has_capture());
+    }
+
+    #[test]
+    fn test_count_and_fill_match_generate() {
+        let positions = [
+            Board::new(),
+            Board::from_bitboards(
+                Bitboard::try_from_notation_vector(&[23]).unwrap(),
+                Bitboard::try_from_notation_vector(&[18, 19, 10, 11]).unwrap(),
+                Bitboard::try_from_notation_vector(&[23]).unwrap(),
+            ),
+            Board::from_bitboards(
+                Bitboard::try_from_notation_vector(&[23, 15, 8]).unwrap(),
+                Bitboard::try_from_notation_vector(&[27]).unwrap(),
+                Bitboard::new(),
+            ),
+        ];
+        for board in positions {
+            for color in [Color::Black, Color::White] {
+                for mode in [CaptureMode::PathDistinct, CaptureMode::PositionDistinct] {
+                    let move_generator = MoveGenerator::new(board, color).with_capture_mode(mode);
+                    let generated = move_generator.generate_moves();
+                    assert_eq!(
+                        move_generator.count_moves(),
+                        generated.len() as u64,
+                        "board= {board}, color= {color:?}, mode= {mode:?}"
+                    );
+                    // filling reuses the list, discarding what it held
+                    let mut moves = MoveList::new();
+                    moves.push(Move::new(Bitboard::new(), Bitboard::new()));
+                    move_generator.fill_moves(&mut moves);
+                    assert_eq!(moves.len(), generated.len());
+                    assert!(moves.iter().zip(&generated).all(|(a, b)| a.same_route(b)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_capture_routes() {
+        // kings land on squares 1-4, 9-12, 17-20 and 25-28 and capture over
+        // the nine men between them; every subset of those squares is tried
+        let landings = [1, 2, 3, 4, 9, 10, 11, 12, 17, 18, 19, 20, 25, 26, 27, 28];
+        let men = Bitboard::try_from_notation_vector(&[6, 7, 8, 14, 15, 16, 22, 23, 24]).unwrap();
+        let mut moves = MoveList::new();
+        let mut max = 0;
+        for subset in 1u32..1 << landings.len() {
+            let squares: Vec<u8> = (0..landings.len())
+                .filter(|i| subset & 1 << i != 0)
+                .map(|i| landings[i])
+                .collect();
+            let kings = Bitboard::try_from_notation_vector(&squares).unwrap();
+            let board = Board::from_bitboards(men, kings, kings);
+            MoveGenerator::new(board, Color::White).fill_moves(&mut moves);
+            max = max.max(moves.len());
+        }
+        assert_eq!(max, 34);
+        assert!(2 * max <= MAX_MOVES && 49 <= MAX_MOVES);
+    }
 }
@@ -0,0 +1,128 @@
+use std::mem::MaybeUninit;
+use std::ops::Deref;
+
+use crate::r#move::Move;
+
+/// Capacity of a [`MoveList`]: the most moves a side can have in any
+/// position, so generation never overflows it.
+///
+/// Without a capture, each of the 49 diagonal links between dark squares
+/// carries at most one move: it needs a piece on one end and an empty
+/// square on the other. A capture lands two rows and two columns from where
+/// it started, so landing squares split into two classes of 16, each with 9
+/// squares that can be jumped over. A man has a subset of a king's routes,
+/// and extra pieces only block routes. So a class has the most routes when
+/// its kings can jump all 9 squares. `test_max_capture_routes` checks that
+/// this is 34, which gives at most 68 captures.
+pub const MAX_MOVES: usize = 68;
+
+/// A fixed-capacity list of moves kept on the stack, so move generation does
+/// not allocate.
+#[derive(Clone)]
+pub struct MoveList {
+    // left uninitialized past `len`: clearing the whole array at every node
+    // would cost more than the allocation this list replaces
+    moves: [MaybeUninit<Move>; MAX_MOVES],
+    len: usize,
+}
+
+impl Default for MoveList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MoveList {
+    pub fn new() -> Self {
+        MoveList {
+            moves: [const { MaybeUninit::uninit() }; MAX_MOVES],
+            len: 0,
+        }
+    }
+
+    /// Appends `m`. Panics when the list already holds [`MAX_MOVES`] moves.
+    #[inline]
+    pub fn push(&mut self, m: Move) {
+        assert!(self.len < MAX_MOVES, "move list capacity exceeded");
+        self.moves[self.len].write(m);
+        self.len += 1;
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Removes the move at `index`, keeping the order of the others.
+    pub fn remove(&mut self, index: usize) -> Move {
+        let m = self[index];
+        self.moves.copy_within(index + 1..self.len, index);
+        self.len -= 1;
+        m
+    }
+
+    pub fn as_slice(&self) -> &[Move] {
+        // SAFETY: the first `len` elements were written by `push`, and
+        // `MaybeUninit<Move>` has the same layout as `Move`
+        unsafe { std::slice::from_raw_parts(self.moves.as_ptr().cast::<Move>(), self.len) }
+    }
+}
+
+impl Deref for MoveList {
+    type Target = [Move];
+
+    fn deref(&self) -> &[Move] {
+        self.as_slice()
+    }
+}
+
+impl<'a> IntoIterator for &'a MoveList {
+    type Item = &'a Move;
+    type IntoIter = std::slice::Iter<'a, Move>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.as_slice().iter()
+    }
+}
+
+impl std::fmt::Debug for MoveList {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bitboard::Bitboard;
+    use crate::move_list::{MoveList, MAX_MOVES};
+    use crate::r#move::Move;
+
+    fn mv(square: u8) -> Move {
+        Move::new(
+            Bitboard::try_from_notation_vector(&[square]).unwrap(),
+            Bitboard::new(),
+        )
+    }
+
+    #[test]
+    fn test_push_remove() {
+        let mut moves = MoveList::new();
+        assert!(moves.is_empty());
+        for square in 1..=4 {
+            moves.push(mv(square));
+        }
+        assert_eq!(moves.remove(1), mv(2));
+        assert_eq!(moves.as_slice(), &[mv(1), mv(3), mv(4)]);
+        moves.clear();
+        assert_eq!(moves.len(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "move list capacity exceeded")]
+    fn test_capacity() {
+        let mut moves = MoveList::new();
+        for _ in 0..=MAX_MOVES {
+            moves.push(mv(1));
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use std::fmt;
+
+use crate::board::{Board, Color};
+use crate::move_generator::MoveGenerator;
+use crate::r#move::Move;
+
+/// Resolves standard notation such as `11-15`, `22x15` or `9x18x27` to
+/// the legal move `color` can play on `board`. Short capture notation
+/// matches any route between the given squares; the landing squares that
+/// are listed must appear in order. Routes that end in the same position
+/// are interchangeable, so the first generated one is returned.
+pub fn parse_move(notation: &str, board: &Board, color: Color) -> Result<Move, NotationError> {
+    let text = notation.trim().trim_end_matches(['!', '?', '*']);
+    let jump = text.contains(['x', 'X']);
+    if text.is_empty() || (jump && text.contains('-')) {
+        return Err(NotationError::Malformed(notation.to_string()));
+    }
+
+    let mut squares = Vec::new();
+    for part in text.split(['-', 'x', 'X']) {
+        match part.trim().parse::<u8>() {
+            Ok(n) if (1..=32).contains(&n) => squares.push(n),
+            Ok(_) => return Err(NotationError::InvalidSquare(part.to_string())),
+            Err(_) => return Err(NotationError::Malformed(notation.to_string())),
+        }
+    }
+    if squares.len() < 2 || (!jump && squares.len() != 2) {
+        return Err(NotationError::Malformed(notation.to_string()));
+    }
+    let (&origin, landings) = squares.split_first().unwrap();
+
+    let moves = MoveGenerator::new(*board, color).generate_moves();
+    let candidates: Vec<&Move> = moves
+        .iter()
+        .filter(|m| {
+            m.is_jump() == jump
+                && m.origin() == Some(origin)
+                && m.path.last() == landings.last().copied()
+                && is_subsequence(landings, &m.path.as_notation_vector())
+        })
+        .collect();
+
+    match candidates.first() {
+        None => Err(NotationError::Illegal {
+            notation: text.to_string(),
+            legal: moves.iter().map(|m| m.to_notation(board, color)).collect(),
+        }),
+        Some(&&first) if candidates.iter().all(|&&m| m == first) => Ok(first),
+        Some(_) => Err(NotationError::Ambiguous {
+            notation: text.to_string(),
+            candidates: candidates
+                .iter()
+                .map(|m| m.to_notation(board, color))
+                .collect(),
+        }),
+    }
+}
+
+fn is_subsequence(needle: &[u8], haystack: &[u8]) -> bool {
+    let mut haystack = haystack.iter();
+    needle.iter().all(|n| haystack.any(|h| h == n))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotationError {
+    Malformed(String),
+    InvalidSquare(String),
+    Illegal {
+        notation: String,
+        legal: Vec<String>,
+    },
+    Ambiguous {
+        notation: String,
+        candidates: Vec<String>,
+    },
+}
+
+impl fmt::Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotationError::Malformed(s) => write!(f, "malformed move: {s:?}"),
+            NotationError::InvalidSquare(s) => write!(f, "invalid square: {s:?}"),
+            NotationError::Illegal { notation, legal } => write!(
+                f,
+                "illegal move {notation}, legal moves are: {}",
+                legal.join(", ")
+            ),
+            NotationError::Ambiguous {
+                notation,
+                candidates,
+            } => write!(
+                f,
+                "ambiguous move {notation}, could be: {}",
+                candidates.join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for NotationError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bitboard::Bitboard,
+        board::{Board, Color},
+        fen::parse_fen,
+        move_generator::MoveGenerator,
+        notation::{parse_move, NotationError},
+        r#move::{Move, Path},
+    };
+
+    #[test]
+    fn test_notation_round_trip() {
+        let (board, color) = parse_fen("W:W18,24,27,28,K10,K15:B12,16,20,K22,K25,K29").unwrap();
+        for m in MoveGenerator::new(board, color).generate_moves() {
+            let notation = m.to_notation(&board, color);
+            assert_eq!(notation, m.to_string());
+            let parsed = parse_move(&notation, &board, color).unwrap();
+            assert!(parsed.same_route(&m), "notation= {notation}");
+        }
+    }
+
+    #[test]
+    fn test_notation_multi_jump() {
+        let (board, color) = parse_fen("W:W27:B8,15,23").unwrap();
+        let m = Move::with_path(
+            Bitboard::try_from_notation_vector(&[27, 4]).unwrap(),
+            Bitboard::try_from_notation_vector(&[23, 15, 8]).unwrap(),
+            Path::try_from_notation_vector(&[18, 11, 4]).unwrap(),
+        );
+        assert_eq!(m.to_string(), "27x18x11x4");
+        for notation in ["27x18x11x4", "27x4", "27x11x4", "27X4!"] {
+            let parsed = parse_move(notation, &board, color).unwrap();
+            assert!(parsed.same_route(&m), "notation= {notation}");
+        }
+        assert_eq!(
+            parse_move("27x4", &board, color),
+            Ok(Move::new(m.movers, m.jumped))
+        );
+        assert!(matches!(
+            parse_move("27x15x4", &board, color),
+            Err(NotationError::Illegal { .. })
+        ));
+        assert!(matches!(
+            parse_move("27-23", &board, color),
+            Err(NotationError::Illegal { .. })
+        ));
+    }
+
+    #[test]
+    fn test_notation_errors() {
+        let board = Board::new();
+        let color = Color::Black;
+        assert_eq!(
+            parse_move("11-15-19", &board, color),
+            Err(NotationError::Malformed("11-15-19".to_string()))
+        );
+        assert_eq!(
+            parse_move("11x15-19", &board, color),
+            Err(NotationError::Malformed("11x15-19".to_string()))
+        );
+        assert_eq!(
+            parse_move("11-35", &board, color),
+            Err(NotationError::InvalidSquare("35".to_string()))
+        );
+        let err = parse_move("11-14", &board, color).unwrap_err();
+        assert!(matches!(err, NotationError::Illegal { ref legal, .. } if legal.len() == 7));
+        assert!(err
+            .to_string()
+            .starts_with("illegal move 11-14, legal moves are: "));
+    }
+
+    #[test]
+    fn test_notation_ambiguous() {
+        // the king can reach 3 over 22 or over 23, leaving different men
+        let (board, color) = parse_fen("B:W7,14,15,22,23:BK26").unwrap();
+        assert_eq!(
+            parse_move("26x3", &board, color),
+            Err(NotationError::Ambiguous {
+                notation: "26x3".to_string(),
+                candidates: vec!["26x17x10x3".to_string(), "26x19x10x3".to_string()],
+            })
+        );
+        assert_eq!(
+            parse_move("26x17x3", &board, color).unwrap().to_string(),
+            "26x17x10x3"
+        );
+
+        // the circle jump has two routes to the same position
+        let (board, color) = parse_fen("B:W10,11,18,19:BK23").unwrap();
+        assert!(parse_move("23x23", &board, color).is_ok());
+    }
+}
@@ -0,0 +1,625 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+
+use crate::board::{Board, Color};
+use crate::error::Error;
+use crate::fen::{to_fen, FenError};
+use crate::game_json::{Game, Header, MoveDetail, MoveKind, MovePair};
+use crate::notation::{parse_move, NotationError};
+use crate::square::Square;
+
+const RESULTS: [&str; 8] = ["1-0", "0-1", "2-0", "0-2", "1-1", "0-0", "1/2-1/2", "*"];
+
+#[derive(Debug)]
+pub enum PdnError {
+    Io(io::Error),
+    Unterminated(&'static str),
+    /// A `)` without a matching `(`, or a variation left open at the end of
+    /// the game.
+    UnbalancedVariation,
+    Fen(FenError),
+    Move {
+        number: u32,
+        notation: String,
+        error: NotationError,
+    },
+}
+
+impl fmt::Display for PdnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PdnError::Io(e) => write!(f, "unable to read PDN: {e}"),
+            PdnError::Unterminated(what) => write!(f, "unterminated {what}"),
+            PdnError::UnbalancedVariation => write!(f, "unbalanced variation parentheses"),
+            PdnError::Fen(e) => write!(f, "invalid FEN tag: {e}"),
+            PdnError::Move {
+                number,
+                notation,
+                error,
+            } => write!(f, "move {number} ({notation}): {error}"),
+        }
+    }
+}
+
+impl std::error::Error for PdnError {}
+
+impl From<io::Error> for PdnError {
+    fn from(e: io::Error) -> Self {
+        PdnError::Io(e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Tag(String, String),
+    MoveNumber(u32),
+    Move(String),
+    Result(String),
+    VariationStart,
+    VariationEnd,
+    // comments, NAGs and escaped lines carry nothing a `Game` can hold
+    Skipped,
+}
+
+/// Reads characters a line at a time so multi-game files are never held in
+/// memory at once.
+struct CharSource<R> {
+    reader: R,
+    buffer: VecDeque<char>,
+    line_start: bool,
+}
+
+impl<R: BufRead> CharSource<R> {
+    fn peek(&mut self) -> io::Result<Option<char>> {
+        if self.buffer.is_empty() {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            self.buffer.extend(line.chars());
+        }
+        Ok(self.buffer.front().copied())
+    }
+
+    fn next(&mut self) -> io::Result<Option<char>> {
+        let c = self.peek()?;
+        if let Some(c) = c {
+            self.buffer.pop_front();
+            self.line_start = c == '\n';
+        }
+        Ok(c)
+    }
+
+    fn read_until(&mut self, end: char, what: &'static str) -> Result<String, PdnError> {
+        let mut text = String::new();
+        loop {
+            match self.next()? {
+                Some(c) if c == end => return Ok(text),
+                Some(c) => text.push(c),
+                None => return Err(PdnError::Unterminated(what)),
+            }
+        }
+    }
+
+    fn skip_whitespace(&mut self) -> io::Result<()> {
+        while self.peek()?.is_some_and(char::is_whitespace) {
+            self.next()?;
+        }
+        Ok(())
+    }
+
+    // a quoted value may hold `]`, so only the one after it ends the tag
+    fn read_tag(&mut self) -> Result<(String, String), PdnError> {
+        let unterminated = || PdnError::Unterminated("tag pair");
+        self.skip_whitespace()?;
+        let mut name = String::new();
+        while let Some(c) = self.peek()? {
+            if c.is_whitespace() || c == ']' || c == '"' {
+                break;
+            }
+            name.push(c);
+            self.next()?;
+        }
+        self.skip_whitespace()?;
+        if self.peek()? != Some('"') {
+            let value = self.read_until(']', "tag pair")?;
+            return Ok((name, value.trim().to_string()));
+        }
+        self.next()?;
+        let mut value = String::new();
+        loop {
+            match self.next()?.ok_or_else(unterminated)? {
+                '"' => break,
+                '\\' => value.push(self.next()?.ok_or_else(unterminated)?),
+                c => value.push(c),
+            }
+        }
+        self.read_until(']', "tag pair")?;
+        Ok((name, value))
+    }
+}
+
+/// Streams games from a PDN file, one [`Game`] per iteration.
+///
+/// Tag pairs become headers, with a `FEN` tag setting the starting position.
+/// Move numbers, comments, NAGs and variations are skipped, and the main line
+/// is stored with every landing square: short capture notation such as
+/// `26x10` is resolved against the position reached so far.
+pub struct PdnReader<R> {
+    source: CharSource<R>,
+    pending: Option<Token>,
+    done: bool,
+}
+
+impl PdnReader<BufReader<File>> {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Ok(PdnReader::new(BufReader::new(File::open(path)?)))
+    }
+}
+
+impl<R: BufRead> PdnReader<R> {
+    pub fn new(reader: R) -> Self {
+        PdnReader {
+            source: CharSource {
+                reader,
+                buffer: VecDeque::new(),
+                line_start: true,
+            },
+            pending: None,
+            done: false,
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Option<Token>, PdnError> {
+        if let Some(token) = self.pending.take() {
+            return Ok(Some(token));
+        }
+        let source = &mut self.source;
+        loop {
+            let line_start = source.line_start;
+            let Some(c) = source.next()? else {
+                return Ok(None);
+            };
+            let token = match c {
+                c if c.is_whitespace() => continue,
+                '%' if line_start => {
+                    source.read_until('\n', "escaped line").ok();
+                    Token::Skipped
+                }
+                '[' => {
+                    let (name, value) = source.read_tag()?;
+                    Token::Tag(name, value)
+                }
+                '{' => {
+                    source.read_until('}', "comment")?;
+                    Token::Skipped
+                }
+                ';' => {
+                    source.read_until('\n', "comment").ok();
+                    Token::Skipped
+                }
+                '(' => Token::VariationStart,
+                ')' => Token::VariationEnd,
+                _ => {
+                    let mut word = c.to_string();
+                    while let Some(c) = source.peek()? {
+                        if c.is_whitespace() || "[]{}();".contains(c) {
+                            break;
+                        }
+                        word.push(c);
+                        source.next()?;
+                    }
+                    classify(word, &mut self.pending)
+                }
+            };
+            return Ok(Some(token));
+        }
+    }
+
+    fn read_game(&mut self) -> Result<Option<Game>, PdnError> {
+        let mut headers = Vec::new();
+        let mut moves = Vec::new();
+        let mut numbers = Vec::new();
+        let mut number = None;
+        let mut variation_depth = 0u32;
+        let mut unbalanced = false;
+        let mut started = false;
+
+        while let Some(token) = self.next_token()? {
+            started |= token != Token::Skipped;
+            match token {
+                Token::Tag(name, value) => {
+                    if !moves.is_empty() {
+                        self.pending = Some(Token::Tag(name, value));
+                        break;
+                    }
+                    headers.push(Header { name, value });
+                }
+                Token::VariationStart => variation_depth += 1,
+                Token::VariationEnd => match variation_depth.checked_sub(1) {
+                    Some(depth) => variation_depth = depth,
+                    None => unbalanced = true,
+                },
+                _ if variation_depth > 0 => {}
+                Token::MoveNumber(n) => number = Some(n),
+                Token::Move(text) => {
+                    numbers.push(number.take());
+                    moves.push(text);
+                }
+                Token::Result(_) => break,
+                Token::Skipped => {}
+            }
+        }
+
+        if !started {
+            return Ok(None);
+        }
+        // the rest of the game is still read so the next one starts cleanly
+        if unbalanced || variation_depth > 0 {
+            return Err(PdnError::UnbalancedVariation);
+        }
+        build_game(headers, &moves, &numbers).map(Some)
+    }
+}
+
+impl<R: BufRead> Iterator for PdnReader<R> {
+    type Item = Result<Game, PdnError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.read_game() {
+            Ok(Some(game)) => Some(Ok(game)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e @ (PdnError::Io(_) | PdnError::Unterminated(_))) => {
+                self.done = true;
+                Some(Err(e))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// Parses every game in a PDN string.
+pub fn parse_pdn(pdn: &str) -> Result<Vec<Game>, PdnError> {
+    PdnReader::new(pdn.as_bytes()).collect()
+}
+
+/// Parses every game in a PDN file, see [`PdnReader`].
+pub fn parse_games_from_pdn_file<P: AsRef<Path>>(path: P) -> Result<Vec<Game>, Error> {
+    Ok(PdnReader::from_file(path)?.collect::<Result<_, _>>()?)
+}
+
+// movetext lines are wrapped before this many columns
+const LINE_WIDTH: usize = 79;
+
+/// Writes `game` as PDN. When `setup` is given it is written as the `FEN`
+/// tag, replacing any `FEN` header the game already has.
+pub fn to_pdn(game: &Game, setup: Option<(&Board, Color)>) -> String {
+    let mut pdn = String::new();
+    let fen = setup.map(|(board, color)| to_fen(board, color));
+    for header in &game.headers {
+        if header.name == "FEN" && fen.is_some() {
+            continue;
+        }
+        pdn.push_str(&tag(&header.name, &header.value));
+    }
+    if let Some(fen) = &fen {
+        pdn.push_str(&tag("FEN", fen));
+    }
+    pdn.push('\n');
+
+    let mut tokens = Vec::new();
+    for pair in &game.moves {
+        if let Some(black) = &pair.black {
+            tokens.push(format!("{}.", pair.number));
+            tokens.push(black.notation());
+        }
+        if let Some(white) = &pair.white {
+            if pair.black.is_none() {
+                tokens.push(format!("{}...", pair.number));
+            }
+            tokens.push(white.notation());
+        }
+    }
+    tokens.push(game.header("Result").unwrap_or("*").to_string());
+
+    let mut line = String::new();
+    for token in tokens {
+        if !line.is_empty() && line.len() + 1 + token.len() > LINE_WIDTH {
+            pdn.push_str(&line);
+            pdn.push('\n');
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&token);
+    }
+    pdn.push_str(&line);
+    pdn.push('\n');
+    pdn
+}
+
+/// Writes `games` as a multi-game PDN file, separated by blank lines.
+pub fn write_pdn<'a, W, I>(writer: &mut W, games: I) -> io::Result<()>
+where
+    W: Write,
+    I: IntoIterator<Item = &'a Game>,
+{
+    for (i, game) in games.into_iter().enumerate() {
+        if i > 0 {
+            writeln!(writer)?;
+        }
+        writer.write_all(to_pdn(game, None).as_bytes())?;
+    }
+    Ok(())
+}
+
+fn tag(name: &str, value: &str) -> String {
+    format!(
+        "[{} \"{}\"]\n",
+        name,
+        value.replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
+
+// splits a leading move number such as `12.` or `12...` off `word`, leaving
+// anything glued to it in `pending`
+fn classify(word: String, pending: &mut Option<Token>) -> Token {
+    if RESULTS.contains(&word.as_str()) {
+        return Token::Result(word);
+    }
+    if word.starts_with('$') {
+        return Token::Skipped;
+    }
+    let digits = word.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 && word[digits..].starts_with('.') {
+        let rest = word[digits..].trim_start_matches('.');
+        if !rest.is_empty() {
+            *pending = Some(classify(rest.to_string(), &mut None));
+        }
+        return Token::MoveNumber(word[..digits].parse().unwrap_or(0));
+    }
+    Token::Move(word)
+}
+
+fn build_game(
+    headers: Vec<Header>,
+    moves: &[String],
+    numbers: &[Option<u32>],
+) -> Result<Game, PdnError> {
+    let mut game = Game {
+        headers,
+        moves: Vec::new(),
+    };
+    let (mut board, mut color) = game.starting_position().map_err(PdnError::Fen)?;
+    let mut number = 1;
+
+    for (text, &given) in moves.iter().zip(numbers) {
+        number = given.unwrap_or(number);
+        let error = |error| PdnError::Move {
+            number,
+            notation: text.clone(),
+            error,
+        };
+        let detail = move_detail(text, &board, color).map_err(error)?;
+        let m = detail
+            .clone()
+            .into_move()
+            .map_err(|_| error(NotationError::Malformed(text.clone())))?;
+        board.apply_move(color, &m);
+        board.promote_kings();
+
+        match (color, game.moves.last_mut()) {
+            (Color::White, Some(pair)) if pair.white.is_none() && pair.number == number => {
+                pair.white = Some(detail);
+            }
+            _ => game.moves.push(MovePair {
+                number,
+                black: (color == Color::Black).then(|| detail.clone()),
+                white: (color == Color::White).then_some(detail),
+            }),
+        }
+        if color == Color::White {
+            number += 1;
+        }
+        color = color.opposite();
+    }
+
+    Ok(game)
+}
+
+// moves that list every square are kept as written, even when illegal, so
+// validation can report them; short captures are resolved on `board`
+fn move_detail(text: &str, board: &Board, color: Color) -> Result<MoveDetail, NotationError> {
+    let trimmed = text.trim_end_matches(['!', '?']);
+    let kind = if trimmed.contains(['x', 'X']) {
+        MoveKind::Jump
+    } else {
+        MoveKind::Simple
+    };
+    let squares: Vec<u8> = trimmed
+        .split(['-', 'x', 'X'])
+        .map(|s| s.parse::<u8>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| NotationError::Malformed(text.to_string()))?;
+    if squares.len() < 2 {
+        return Err(NotationError::Malformed(text.to_string()));
+    }
+    if let Some(&n) = squares.iter().find(|n| !(1..=32).contains(*n)) {
+        return Err(NotationError::InvalidSquare(n.to_string()));
+    }
+
+    let complete = match kind {
+        MoveKind::Simple => squares.len() == 2,
+        MoveKind::Jump => squares.windows(2).all(|pair| {
+            Square::new(pair[0])
+                .zip(Square::new(pair[1]))
+                .and_then(|(from, to)| Square::jumped_between(from, to))
+                .is_some()
+        }),
+    };
+    if complete {
+        return Ok(MoveDetail {
+            kind,
+            moves: squares,
+        });
+    }
+
+    let m = parse_move(trimmed, board, color)?;
+    let mut moves = vec![m.origin().unwrap_or(squares[0])];
+    moves.extend(m.path.as_notation_vector());
+    Ok(MoveDetail { kind, moves })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        board::{Board, Color},
+        error::Error,
+        fen::parse_fen,
+        game_json::{MoveDetail, MoveKind},
+        notation::{parse_move, NotationError},
+        pdn::{
+            move_detail, parse_games_from_pdn_file, parse_pdn, to_pdn, write_pdn, PdnError,
+            PdnReader,
+        },
+    };
+
+    #[test]
+    fn test_pdn_file_errors() {
+        assert!(matches!(
+            parse_games_from_pdn_file("tests/fixtures/does-not-exist.pdn"),
+            Err(Error::Io(_))
+        ));
+        let error = Error::from(parse_move("9-14", &Board::new(), Color::White).unwrap_err());
+        assert!(matches!(
+            error,
+            Error::Notation(NotationError::Illegal { .. })
+        ));
+    }
+
+    #[test]
+    fn test_pdn_tokens() {
+        let pdn = r#"[Event "Test \"quoted\""]
+[Result "*"]
+% an escaped line [Ignored "tag"]
+1.11-15 {comment (not a variation)} 23-19 $3 2. 8-11 ; rest of line 1-0
+(2. 9-14 (2. 10-14)) 22-17! 3. 11-16 (3... 9-13 17-14) 24-20 *
+"#;
+        let games = parse_pdn(pdn).unwrap();
+        assert_eq!(games.len(), 1);
+        let game = &games[0];
+        assert_eq!(game.header("Event"), Some("Test \"quoted\""));
+        assert_eq!(game.moves.len(), 3);
+        assert_eq!(
+            game.moves[2].white,
+            Some(MoveDetail {
+                kind: MoveKind::Simple,
+                moves: vec![24, 20]
+            })
+        );
+    }
+
+    #[test]
+    fn test_pdn_bracket_in_tag_value() {
+        let games =
+            parse_pdn("[Event \"Round [3]\"]\n[Site \"a \\\\ b\"]\n1. 11-15 23-19 *").unwrap();
+        assert_eq!(games[0].header("Event"), Some("Round [3]"));
+        assert_eq!(games[0].header("Site"), Some("a \\ b"));
+        assert_eq!(games[0].moves.len(), 1);
+        assert!(matches!(
+            parse_pdn("[Event \"Round [3]]"),
+            Err(PdnError::Unterminated("tag pair"))
+        ));
+    }
+
+    #[test]
+    fn test_pdn_short_capture_from_fen() {
+        let pdn = r#"
+            [FEN "W:W27:B8,15,23"]
+            1... 27x4 0-1
+        "#;
+        let games = parse_pdn(pdn).unwrap();
+        assert_eq!(games[0].moves[0].black, None);
+        assert_eq!(
+            games[0].moves[0].white,
+            Some(MoveDetail {
+                kind: MoveKind::Jump,
+                moves: vec![27, 18, 11, 4]
+            })
+        );
+    }
+
+    #[test]
+    fn test_pdn_errors() {
+        assert!(matches!(
+            parse_pdn("1. 11-15 {never closed"),
+            Err(PdnError::Unterminated("comment"))
+        ));
+        assert!(matches!(
+            parse_pdn("1. 11x25 *"),
+            Err(PdnError::Move { number: 1, .. })
+        ));
+        assert!(matches!(
+            parse_pdn("[FEN \"Q\"] 1. 11-15 *"),
+            Err(PdnError::Fen(_))
+        ));
+        // a bit distance of a jump, but over the ghost bit between rows
+        assert!(matches!(
+            move_detail("29x28", &Board::new(), Color::White),
+            Err(NotationError::Illegal { .. })
+        ));
+
+        // a stray `)` fails its own game without leaking into the next one
+        let pdn = "1. 11-15 23-19 ) 2. 8-11 *\n\n1. 11-15 (1. 9-14) 23-19 *";
+        let mut reader = PdnReader::new(pdn.as_bytes());
+        assert!(matches!(
+            reader.next(),
+            Some(Err(PdnError::UnbalancedVariation))
+        ));
+        assert_eq!(reader.next().unwrap().unwrap().moves.len(), 1);
+        assert!(reader.next().is_none());
+        assert!(matches!(
+            parse_pdn("1. 11-15 (1. 9-14 23-19 *"),
+            Err(PdnError::UnbalancedVariation)
+        ));
+    }
+
+    #[test]
+    fn test_pdn_write() {
+        let pdn = r#"[Event "Escapes \"\\\""]
+[Result "0-1"]
+
+1. 11-15 23-19 2. 8-11 22-17 3. 15x24 28x19 0-1
+"#;
+        let games = parse_pdn(pdn).unwrap();
+        assert_eq!(games[0].header("Event"), Some(r#"Escapes "\""#));
+        assert_eq!(to_pdn(&games[0], None), pdn);
+
+        let mut written = Vec::new();
+        write_pdn(&mut written, games.iter().chain(&games)).unwrap();
+        let reparsed = parse_pdn(std::str::from_utf8(&written).unwrap()).unwrap();
+        assert_eq!(reparsed, [games[0].clone(), games[0].clone()]);
+    }
+
+    #[test]
+    fn test_pdn_write_setup() {
+        // the short capture can only be resolved from the setup position
+        assert!(parse_pdn("1... 27x4 0-1").is_err());
+
+        let games = parse_pdn("[FEN \"W:W27:B8,15,23\"] 1... 27x4").unwrap();
+        let (board, color) = parse_fen("W:W27:B8,15,23").unwrap();
+        let pdn = to_pdn(&games[0], Some((&board, color)));
+        assert_eq!(pdn, "[FEN \"W:W27:B8,15,23\"]\n\n1... 27x18x11x4 *\n");
+        assert_eq!(parse_pdn(&pdn).unwrap(), games);
+    }
+}
@@ -1,35 +1,371 @@
 use crate::{
     board::{Board, Color},
     move_generator::MoveGenerator,
+    move_list::MoveList,
+    r#move::Move,
+    transposition::TranspositionTable,
+    zobrist,
 };
 use rayon::prelude::*;
+use serde::Serialize;
+
+/// Known perft results for the initial position with Black to move, indexed
+/// by depth.
+pub const INITIAL_POSITION_PERFT: &[u64] = &[
+    1,
+    7,
+    49,
+    302,
+    1469,
+    7361,
+    36768,
+    179740,
+    845931,
+    3963680,
+    18391564,
+    85242128,
+    388623673,
+    1766623630,
+    7978439499,
+    36263167175,
+    165629569428,
+];
+
+/// Node count of the subtree below a single root move.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DivideEntry {
+    #[serde(skip)]
+    pub mv: Move,
+    pub notation: String,
+    pub nodes: u64,
+}
+
+/// Counters for the positions reached at one ply of a perft search. Move
+/// counters describe the moves that led to those positions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct PlyStats {
+    pub nodes: u64,
+    pub captures: u64,
+    pub multi_captures: u64,
+    pub pieces_captured: u64,
+    pub promotions: u64,
+    pub game_overs: u64,
+}
+
+impl std::ops::AddAssign for PlyStats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.nodes += rhs.nodes;
+        self.captures += rhs.captures;
+        self.multi_captures += rhs.multi_captures;
+        self.pieces_captured += rhs.pieces_captured;
+        self.promotions += rhs.promotions;
+        self.game_overs += rhs.game_overs;
+    }
+}
+
+/// Per-ply breakdown of a perft search; `plies[0]` is the root position.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PerftStats {
+    pub plies: Vec<PlyStats>,
+}
+
+impl PerftStats {
+    fn with_depth(depth: u32) -> Self {
+        PerftStats {
+            plies: vec![PlyStats::default(); depth as usize + 1],
+        }
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        for (ply, stats) in self.plies.iter_mut().zip(other.plies) {
+            *ply += stats;
+        }
+        self
+    }
+
+    /// Number of positions at the deepest ply, the same value [`perft`] returns.
+    pub fn nodes(&self) -> u64 {
+        self.plies.last().map_or(0, |p| p.nodes)
+    }
+}
+
+/// Node counts under both ways of treating capture routes that end in the
+/// same position, see [`CaptureMode`](crate::move_generator::CaptureMode).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct PerftCounts {
+    pub paths: u64,
+    pub positions: u64,
+}
+
+impl std::ops::Add for PerftCounts {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        PerftCounts {
+            paths: self.paths + rhs.paths,
+            positions: self.positions + rhs.positions,
+        }
+    }
+}
 
 fn perft_recur(color: Color, board: &Board, depth: u32) -> u64 {
     if depth == 0 {
         return 1;
     }
 
+    let mut moves = MoveList::new();
+    MoveGenerator::new(*board, color).fill_moves(&mut moves);
+    let mut count = 0;
+
+    for m in &moves {
+        let mut new_board = *board;
+        new_board.apply_move(color, m);
+        new_board.promote_kings();
+        count += perft_recur(color.opposite(), &new_board, depth - 1);
+    }
+
+    count
+}
+
+fn perft_stats_recur(color: Color, board: &Board, ply: usize, depth: u32, stats: &mut PerftStats) {
+    stats.plies[ply].nodes += 1;
+
     let move_generator = MoveGenerator::new(*board, color);
+    if depth == 0 {
+        if !move_generator.has_legal_move() {
+            stats.plies[ply].game_overs += 1;
+        }
+        return;
+    }
+
     let moves = move_generator.generate_moves();
+    if moves.is_empty() {
+        stats.plies[ply].game_overs += 1;
+        return;
+    }
+
+    for m in moves {
+        let mut new_board = *board;
+        new_board.apply_move(color, &m);
+        let new_kings = new_board.promote_kings();
+        count_move(&mut stats.plies[ply + 1], &m, new_kings.len());
+        perft_stats_recur(color.opposite(), &new_board, ply + 1, depth - 1, stats);
+    }
+}
+
+#[inline]
+fn count_move(stats: &mut PlyStats, m: &Move, promotions: usize) {
+    if m.is_jump() {
+        stats.captures += 1;
+        stats.pieces_captured += m.jumped.len() as u64;
+        if m.jumped.len() > 1 {
+            stats.multi_captures += 1;
+        }
+    }
+    stats.promotions += promotions as u64;
+}
+
+// at depth 1 the number of legal moves is the node count, so the last ply
+// is never applied to a board
+fn perft_bulk_recur(color: Color, board: &Board, depth: u32) -> u64 {
+    let move_generator = MoveGenerator::new(*board, color);
+    match depth {
+        0 => 1,
+        1 => move_generator.count_moves(),
+        _ => {
+            let mut moves = MoveList::new();
+            move_generator.fill_moves(&mut moves);
+            let mut count = 0;
+            for m in &moves {
+                let mut new_board = *board;
+                new_board.apply_move(color, m);
+                new_board.promote_kings();
+                count += perft_bulk_recur(color.opposite(), &new_board, depth - 1);
+            }
+            count
+        }
+    }
+}
+
+// same count as perft_recur, or perft_bulk_recur when `bulk` is set, but
+// backtracking with make/unmake on a single board instead of copying it
+fn perft_unmake_recur(color: Color, board: &mut Board, depth: u32, bulk: bool) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    let move_generator = MoveGenerator::new(*board, color);
+    if bulk && depth == 1 {
+        return move_generator.count_moves();
+    }
+
+    let mut moves = MoveList::new();
+    move_generator.fill_moves(&mut moves);
     let mut count = 0;
+    for m in &moves {
+        let undo = board.make_move(color, m);
+        count += perft_unmake_recur(color.opposite(), board, depth - 1, bulk);
+        board.unmake_move(color, m, &undo);
+    }
+    count
+}
 
+// groups path distinct moves by resulting position, keeping the first move
+// of each group and how many routes reach it
+fn group_by_position(moves: Vec<Move>) -> Vec<(Move, u64)> {
+    let mut groups: Vec<(Move, u64)> = Vec::with_capacity(moves.len());
     for m in moves {
+        match groups.iter_mut().find(|(g, _)| *g == m) {
+            Some((_, routes)) => *routes += 1,
+            None => groups.push((m, 1)),
+        }
+    }
+    groups
+}
+
+fn perft_counts_recur(color: Color, board: &Board, depth: u32) -> PerftCounts {
+    if depth == 0 {
+        return PerftCounts {
+            paths: 1,
+            positions: 1,
+        };
+    }
+
+    let move_generator = MoveGenerator::new(*board, color);
+    let mut counts = PerftCounts::default();
+
+    for (m, routes) in group_by_position(move_generator.generate_moves()) {
         let mut new_board = *board;
         new_board.apply_move(color, &m);
         new_board.promote_kings();
-        count += perft_recur(color.opposite(), &new_board, depth - 1);
+        let child = perft_counts_recur(color.opposite(), &new_board, depth - 1);
+        counts.paths += routes * child.paths;
+        counts.positions += child.positions;
+    }
+
+    counts
+}
+
+// the same position is counted differently at different remaining depths,
+// so the depth is folded into the table key
+#[inline]
+fn depth_key(key: u64, depth: u32) -> u64 {
+    key ^ u64::from(depth).wrapping_mul(0x9e3779b97f4a7c15)
+}
+
+fn perft_hashed_recur(
+    color: Color,
+    board: &Board,
+    key: u64,
+    depth: u32,
+    tt: &TranspositionTable,
+) -> u64 {
+    if depth <= 1 {
+        return perft_bulk_recur(color, board, depth);
+    }
+
+    let tt_key = depth_key(key, depth);
+    if let Some(count) = tt.probe(tt_key) {
+        return count;
     }
 
+    let mut moves = MoveList::new();
+    MoveGenerator::new(*board, color).fill_moves(&mut moves);
+    let mut count = 0;
+
+    for m in &moves {
+        let mut new_board = *board;
+        let new_key = zobrist::update_move(key, board, color, m);
+        new_board.apply_move(color, m);
+        let new_kings = new_board.promote_kings();
+        let new_key = zobrist::update_promotion(new_key, &new_board, new_kings);
+        count += perft_hashed_recur(color.opposite(), &new_board, new_key, depth - 1, tt);
+    }
+
+    tt.store(tt_key, count);
     count
 }
 
-pub fn perft(color: Color, board: &Board, depth: u32) -> u64 {
-    if depth == 0 {
-        return 1;
+/// Controls how [`perft_with`] and [`divide_with`] search. The number of
+/// worker threads is that of the rayon pool the search runs in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PerftOptions {
+    /// Count the last ply from the number of generated moves.
+    pub bulk: bool,
+    /// Number of plies, starting at the root, whose moves are searched in
+    /// parallel. `None`, the default as in the CLI, keeps splitting until
+    /// there are enough subtrees to keep every thread busy, which helps when
+    /// the root has few moves.
+    pub split_depth: Option<u32>,
+    /// Backtrack with [`Board::make_move`] and [`Board::unmake_move`] instead
+    /// of copying the board at every node.
+    pub make_unmake: bool,
+}
+
+// subtrees wanted per thread before automatic splitting stops
+const TASKS_PER_THREAD: usize = 8;
+
+#[derive(Debug, Clone, Copy)]
+struct Split {
+    plies: Option<u32>,
+    width: usize,
+}
+
+impl Split {
+    fn new(split_depth: Option<u32>) -> Self {
+        Split {
+            plies: split_depth,
+            width: 1,
+        }
+    }
+
+    fn active(&self) -> bool {
+        match self.plies {
+            Some(plies) => plies > 0,
+            None => self.width < rayon::current_num_threads() * TASKS_PER_THREAD,
+        }
+    }
+
+    fn next(&self, moves: usize) -> Self {
+        Split {
+            plies: self.plies.map(|plies| plies.saturating_sub(1)),
+            width: self.width.saturating_mul(moves.max(1)),
+        }
+    }
+}
+
+fn search(
+    color: Color,
+    board: &Board,
+    depth: u32,
+    options: &PerftOptions,
+    tt: Option<&TranspositionTable>,
+) -> u64 {
+    match tt {
+        Some(tt) => perft_hashed_recur(color, board, zobrist::hash(board, color), depth, tt),
+        None if options.make_unmake => {
+            let mut scratch = *board;
+            perft_unmake_recur(color, &mut scratch, depth, options.bulk)
+        }
+        None if options.bulk => perft_bulk_recur(color, board, depth),
+        None => perft_recur(color, board, depth),
+    }
+}
+
+fn perft_split(
+    color: Color,
+    board: &Board,
+    depth: u32,
+    split: Split,
+    options: &PerftOptions,
+    tt: Option<&TranspositionTable>,
+) -> u64 {
+    if depth == 0 || !split.active() {
+        return search(color, board, depth, options, tt);
     }
 
     let move_generator = MoveGenerator::new(*board, color);
     let moves = move_generator.generate_moves();
+    let next = split.next(moves.len());
 
     moves
         .par_iter()
@@ -37,7 +373,400 @@ pub fn perft(color: Color, board: &Board, depth: u32) -> u64 {
             let mut new_board = *board;
             new_board.apply_move(color, m);
             new_board.promote_kings();
-            perft_recur(color.opposite(), &new_board, depth - 1)
+            perft_split(color.opposite(), &new_board, depth - 1, next, options, tt)
         })
         .sum()
 }
+
+/// Runs perft as configured by `options`. With a transposition table,
+/// subtrees reached through different move orders are looked up in `tt`
+/// instead of being searched again, and the last ply is always bulk counted.
+pub fn perft_with(
+    color: Color,
+    board: &Board,
+    depth: u32,
+    options: &PerftOptions,
+    tt: Option<&TranspositionTable>,
+) -> u64 {
+    perft_split(
+        color,
+        board,
+        depth,
+        Split::new(options.split_depth),
+        options,
+        tt,
+    )
+}
+
+/// [`divide`] as configured by `options`, see [`perft_with`].
+pub fn divide_with(
+    color: Color,
+    board: &Board,
+    depth: u32,
+    options: &PerftOptions,
+    tt: Option<&TranspositionTable>,
+) -> Vec<DivideEntry> {
+    if depth == 0 {
+        return Vec::new();
+    }
+
+    let split = Split::new(options.split_depth);
+    let move_generator = MoveGenerator::new(*board, color);
+    let moves = move_generator.generate_moves();
+    let next = split.next(moves.len());
+
+    moves
+        .par_iter()
+        .with_min_len(if split.active() { 1 } else { usize::MAX })
+        .map(|m| {
+            let mut new_board = *board;
+            new_board.apply_move(color, m);
+            new_board.promote_kings();
+            DivideEntry {
+                mv: *m,
+                notation: m.to_notation(board, color),
+                nodes: perft_split(color.opposite(), &new_board, depth - 1, next, options, tt),
+            }
+        })
+        .collect()
+}
+
+pub fn perft(color: Color, board: &Board, depth: u32) -> u64 {
+    perft_with(color, board, depth, &PerftOptions::default(), None)
+}
+
+/// Runs perft separately below each root move, in generation order, so
+/// counts can be compared move by move against a reference engine. At depth
+/// 0 no move is played, so there are no entries even though perft(0) is 1.
+pub fn divide(color: Color, board: &Board, depth: u32) -> Vec<DivideEntry> {
+    divide_with(color, board, depth, &PerftOptions::default(), None)
+}
+
+/// Same count as [`perft`], using bulk counting at the last ply.
+pub fn perft_bulk(color: Color, board: &Board, depth: u32) -> u64 {
+    let options = PerftOptions {
+        bulk: true,
+        ..PerftOptions::default()
+    };
+    perft_with(color, board, depth, &options, None)
+}
+
+/// Same count as [`perft`], backtracking with make/unmake instead of copying
+/// the board.
+pub fn perft_make_unmake(color: Color, board: &Board, depth: u32) -> u64 {
+    let options = PerftOptions {
+        make_unmake: true,
+        ..PerftOptions::default()
+    };
+    perft_with(color, board, depth, &options, None)
+}
+
+/// [`divide`] using bulk counting at the last ply.
+pub fn divide_bulk(color: Color, board: &Board, depth: u32) -> Vec<DivideEntry> {
+    let options = PerftOptions {
+        bulk: true,
+        ..PerftOptions::default()
+    };
+    divide_with(color, board, depth, &options, None)
+}
+
+/// Runs perft while collecting [`PerftStats`] for every ply up to `depth`.
+/// Positions at the deepest ply still have their moves generated to detect
+/// game overs.
+pub fn perft_stats(color: Color, board: &Board, depth: u32) -> PerftStats {
+    let mut root = PerftStats::with_depth(depth);
+    root.plies[0].nodes = 1;
+
+    let move_generator = MoveGenerator::new(*board, color);
+    let moves = move_generator.generate_moves();
+    if moves.is_empty() {
+        root.plies[0].game_overs = 1;
+        return root;
+    }
+    if depth == 0 {
+        return root;
+    }
+
+    let below = moves
+        .par_iter()
+        .map(|m| {
+            let mut stats = PerftStats::with_depth(depth);
+            let mut new_board = *board;
+            new_board.apply_move(color, m);
+            let new_kings = new_board.promote_kings();
+            count_move(&mut stats.plies[1], m, new_kings.len());
+            perft_stats_recur(color.opposite(), &new_board, 1, depth - 1, &mut stats);
+            stats
+        })
+        .reduce(|| PerftStats::with_depth(depth), PerftStats::merge);
+    root.merge(below)
+}
+
+/// Same count as [`perft`], but subtrees reached through different move
+/// orders are looked up in `tt` instead of being searched again. The last
+/// ply is bulk counted.
+pub fn perft_hashed(color: Color, board: &Board, depth: u32, tt: &TranspositionTable) -> u64 {
+    perft_with(color, board, depth, &PerftOptions::default(), Some(tt))
+}
+
+/// [`divide`] backed by a transposition table.
+pub fn divide_hashed(
+    color: Color,
+    board: &Board,
+    depth: u32,
+    tt: &TranspositionTable,
+) -> Vec<DivideEntry> {
+    divide_with(color, board, depth, &PerftOptions::default(), Some(tt))
+}
+
+/// Counts both path distinct nodes, the same value [`perft`] returns, and
+/// position distinct nodes, where capture routes that reach the same
+/// position are searched once.
+pub fn perft_counts(color: Color, board: &Board, depth: u32) -> PerftCounts {
+    if depth == 0 {
+        return perft_counts_recur(color, board, depth);
+    }
+
+    let move_generator = MoveGenerator::new(*board, color);
+    let groups = group_by_position(move_generator.generate_moves());
+
+    groups
+        .par_iter()
+        .map(|(m, routes)| {
+            let mut new_board = *board;
+            new_board.apply_move(color, m);
+            new_board.promote_kings();
+            let child = perft_counts_recur(color.opposite(), &new_board, depth - 1);
+            PerftCounts {
+                paths: routes * child.paths,
+                positions: child.positions,
+            }
+        })
+        .reduce(PerftCounts::default, |a, b| a + b)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        board::{Board, Color},
+        fen::parse_fen,
+        move_generator::{CaptureMode, MoveGenerator},
+        perft::{
+            divide, divide_bulk, divide_hashed, divide_with, perft, perft_bulk, perft_counts,
+            perft_hashed, perft_make_unmake, perft_stats, perft_with, PerftCounts, PerftOptions,
+            PlyStats, INITIAL_POSITION_PERFT,
+        },
+        transposition::TranspositionTable,
+    };
+
+    #[test]
+    fn test_perft_initial_position() {
+        let board = Board::new();
+        for (depth, &nodes) in INITIAL_POSITION_PERFT.iter().enumerate().take(8) {
+            assert_eq!(perft(Color::Black, &board, depth as u32), nodes);
+        }
+    }
+
+    #[test]
+    fn test_divide_sums_to_perft() {
+        let board = Board::new();
+        let entries = divide(Color::Black, &board, 5);
+        assert_eq!(entries.len(), 7);
+        assert_eq!(
+            entries.iter().map(|e| e.nodes).sum::<u64>(),
+            perft(Color::Black, &board, 5)
+        );
+        let notation: Vec<&str> = entries.iter().map(|e| e.notation.as_str()).collect();
+        for expected in ["9-13", "9-14", "10-14", "10-15", "11-15", "11-16", "12-16"] {
+            assert!(
+                notation.contains(&expected),
+                "missing {expected} in {notation:?}"
+            );
+        }
+        assert!(divide(Color::Black, &board, 0).is_empty());
+    }
+
+    #[test]
+    fn test_perft_hashed_matches_perft() {
+        let tt = TranspositionTable::new(4);
+        let board = Board::new();
+        for depth in 0..=8 {
+            assert_eq!(
+                perft_hashed(Color::Black, &board, depth, &tt),
+                perft(Color::Black, &board, depth)
+            );
+        }
+
+        let (board, color) = parse_fen("W:W18,24,27,28,K10,K15:B12,16,20,K22,K25,K29").unwrap();
+        tt.clear();
+        assert_eq!(
+            divide_hashed(color, &board, 6, &tt),
+            divide(color, &board, 6)
+        );
+    }
+
+    #[test]
+    fn test_perft_bulk_matches_perft() {
+        let board = Board::new();
+        for depth in 0..=8 {
+            assert_eq!(
+                perft_bulk(Color::Black, &board, depth),
+                perft(Color::Black, &board, depth)
+            );
+        }
+
+        let (board, color) = parse_fen("W:W18,24,27,28,K10,K15:B12,16,20,K22,K25,K29").unwrap();
+        assert_eq!(divide_bulk(color, &board, 6), divide(color, &board, 6));
+    }
+
+    fn check_unmake(color: Color, board: &mut Board, depth: u32) {
+        if depth == 0 {
+            return;
+        }
+        for m in MoveGenerator::new(*board, color).generate_moves() {
+            let before = *board;
+            let mut copied = before;
+            copied.apply_move(color, &m);
+            let promoted = copied.promote_kings();
+
+            let undo = board.make_move(color, &m);
+            assert_eq!(*board, copied);
+            assert_eq!(undo.promoted, promoted);
+            check_unmake(color.opposite(), board, depth - 1);
+            board.unmake_move(color, &m, &undo);
+            assert_eq!(*board, before, "move= {m}");
+        }
+    }
+
+    #[test]
+    fn test_make_unmake_restores_board() {
+        // kings on both sides, captures of kings and promotions within reach
+        for fen in [
+            "W:W18,24,27,28,K10,K15:B12,16,20,K22,K25,K29",
+            "B:W6,7,K14,K23:B19,22,26,K11",
+        ] {
+            let (mut board, color) = parse_fen(fen).unwrap();
+            check_unmake(color, &mut board, 5);
+        }
+    }
+
+    #[test]
+    fn test_perft_make_unmake_matches_perft() {
+        let board = Board::new();
+        for depth in 0..=8 {
+            assert_eq!(
+                perft_make_unmake(Color::Black, &board, depth),
+                perft(Color::Black, &board, depth)
+            );
+        }
+
+        let (board, color) = parse_fen("W:W18,24,27,28,K10,K15:B12,16,20,K22,K25,K29").unwrap();
+        let options = PerftOptions {
+            bulk: true,
+            make_unmake: true,
+            ..PerftOptions::default()
+        };
+        assert_eq!(
+            perft_with(color, &board, 6, &options, None),
+            perft(color, &board, 6)
+        );
+    }
+
+    #[test]
+    fn test_perft_stats_nodes_match_perft() {
+        let board = Board::new();
+        let stats = perft_stats(Color::Black, &board, 7);
+        assert_eq!(stats.plies.len(), 8);
+        for (ply, stats) in stats.plies.iter().enumerate() {
+            assert_eq!(stats.nodes, perft(Color::Black, &board, ply as u32));
+        }
+        assert_eq!(stats.nodes(), 179740);
+        assert_eq!(stats.plies[1].captures, 0);
+        assert_eq!(stats.plies[2].captures, 0);
+        assert!(stats.plies[3].captures > 0);
+    }
+
+    #[test]
+    fn test_perft_stats_captures_and_game_over() {
+        // a single white man that can triple jump into the king row, after
+        // which black has nothing left
+        let (board, color) = parse_fen("W:W27:B8,15,23").unwrap();
+        let stats = perft_stats(color, &board, 1);
+        assert_eq!(
+            stats.plies[1],
+            PlyStats {
+                nodes: 1,
+                captures: 1,
+                multi_captures: 1,
+                pieces_captured: 3,
+                promotions: 1,
+                game_overs: 1,
+            }
+        );
+
+        let (board, color) = parse_fen("B:WK1:B").unwrap();
+        let stats = perft_stats(color, &board, 3);
+        assert_eq!(stats.plies[0].game_overs, 1);
+        assert_eq!(stats.nodes(), 0);
+    }
+
+    fn perft_positions(color: Color, board: &Board, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        MoveGenerator::new(*board, color)
+            .with_capture_mode(CaptureMode::PositionDistinct)
+            .generate_moves()
+            .iter()
+            .map(|m| {
+                let mut new_board = *board;
+                new_board.apply_move(color, m);
+                new_board.promote_kings();
+                perft_positions(color.opposite(), &new_board, depth - 1)
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_perft_counts() {
+        let board = Board::new();
+        let counts = perft_counts(Color::Black, &board, 7);
+        assert_eq!(counts.paths, perft(Color::Black, &board, 7));
+        assert_eq!(counts.positions, perft_positions(Color::Black, &board, 7));
+
+        // black king with two routes around the same four white men
+        let (board, color) = parse_fen("B:W10,11,18,19:BK23").unwrap();
+        assert_eq!(
+            perft_counts(color, &board, 1),
+            PerftCounts {
+                paths: 2,
+                positions: 1
+            }
+        );
+        for depth in 0..=6 {
+            let counts = perft_counts(color, &board, depth);
+            assert_eq!(counts.paths, perft(color, &board, depth));
+            assert_eq!(counts.positions, perft_positions(color, &board, depth));
+        }
+    }
+
+    #[test]
+    fn test_split_depth() {
+        let (board, color) = parse_fen("W:W18,24,27,28,K10,K15:B12,16,20,K22,K25,K29").unwrap();
+        let expected = perft(color, &board, 6);
+        for split_depth in [Some(0), Some(1), Some(3), Some(10), None] {
+            for bulk in [false, true] {
+                let options = PerftOptions {
+                    bulk,
+                    split_depth,
+                    ..PerftOptions::default()
+                };
+                assert_eq!(perft_with(color, &board, 6, &options, None), expected);
+                assert_eq!(
+                    divide_with(color, &board, 6, &options, None),
+                    divide(color, &board, 6)
+                );
+            }
+        }
+    }
+}
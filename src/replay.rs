@@ -0,0 +1,145 @@
+use std::fmt;
+
+use crate::board::{Board, Color};
+use crate::error::Error;
+use crate::game_json::Game;
+use crate::move_generator::MoveGenerator;
+
+/// The first move of a game that is not among the legal moves of its
+/// position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IllegalMove {
+    pub number: u32,
+    pub color: Color,
+    /// The move as recorded in the game.
+    pub played: String,
+    /// Every legal move in the position, in generation order.
+    pub candidates: Vec<String>,
+    /// Position before the move.
+    pub board: Board,
+}
+
+impl fmt::Display for IllegalMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "move {} ({:?}) {} is illegal, expected one of [{}]",
+            self.number,
+            self.color,
+            self.played,
+            self.candidates.join(", ")
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    /// The game could not be read: a bad FEN header, a malformed move or
+    /// moves out of turn.
+    Invalid(Error),
+    Illegal(Box<IllegalMove>),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Invalid(e) => write!(f, "{e}"),
+            ReplayError::Illegal(illegal) => write!(f, "{illegal}"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReplayError::Invalid(e) => Some(e),
+            ReplayError::Illegal(_) => None,
+        }
+    }
+}
+
+impl From<Error> for ReplayError {
+    fn from(e: Error) -> Self {
+        ReplayError::Invalid(e)
+    }
+}
+
+/// Plays through `game` from its starting position, checking every move
+/// against [`MoveGenerator`]. Returns the starting board followed by the
+/// board after each move.
+pub fn replay(game: &Game) -> Result<Vec<Board>, ReplayError> {
+    let (mut board, mut color) = game.starting_position().map_err(Error::from)?;
+    let mut boards = vec![board];
+
+    let details = game.moves.iter().flat_map(|pair| {
+        [
+            (pair.number, Color::Black, &pair.black),
+            (pair.number, Color::White, &pair.white),
+        ]
+    });
+    for (number, mover, detail) in details {
+        let Some(detail) = detail else {
+            continue;
+        };
+        if mover != color {
+            return Err(Error::MalformedMove(format!(
+                "move {number} is recorded for {mover:?} but {color:?} is to move"
+            ))
+            .into());
+        }
+        let played = detail.clone().into_move()?;
+        let moves = MoveGenerator::new(board, color).generate_moves();
+        if !moves.contains(&played) {
+            return Err(ReplayError::Illegal(Box::new(IllegalMove {
+                number,
+                color,
+                played: detail.notation(),
+                candidates: moves.iter().map(|m| m.to_notation(&board, color)).collect(),
+                board,
+            })));
+        }
+        board.apply_move(color, &played);
+        board.promote_kings();
+        boards.push(board);
+        color = color.opposite();
+    }
+
+    Ok(boards)
+}
+
+/// Checks that every move of `game` is legal.
+pub fn validate(game: &Game) -> Result<(), ReplayError> {
+    replay(game).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, Color};
+    use crate::fen::parse_fen;
+    use crate::pdn::parse_pdn;
+    use crate::replay::{replay, validate, ReplayError};
+
+    #[test]
+    fn test_replay_boards() {
+        let games = parse_pdn("1. 11-15 23-19 2. 8-11 *").unwrap();
+        let boards = replay(&games[0]).unwrap();
+        assert_eq!(boards.len(), 4);
+        assert_eq!(boards[0], Board::new());
+        let (expected, _) = parse_fen("W:W19,21,22,24-32:B1-7,9-12,15").unwrap();
+        assert_eq!(boards[3], expected);
+    }
+
+    #[test]
+    fn test_validate_illegal() {
+        let games = parse_pdn("1. 11-15 23-19 2. 15-19 *").unwrap();
+        match validate(&games[0]) {
+            Err(ReplayError::Illegal(illegal)) => {
+                assert_eq!(illegal.number, 2);
+                assert_eq!(illegal.color, Color::Black);
+                assert_eq!(illegal.played, "15-19");
+                assert!(illegal.candidates.contains(&"8-11".to_string()));
+            }
+            other => panic!("expected an illegal move, found {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use std::fmt;
+
+use crate::bitboard::Bitboard;
+use crate::error::{Error, Result};
+
+// bit index of each square in the padded layout, in notation order
+const BIT_INDICES: [u8; 32] = [
+    37, 38, 39, 40, 32, 33, 34, 35, 28, 29, 30, 31, 23, 24, 25, 26, 19, 20, 21, 22, 14, 15, 16, 17,
+    10, 11, 12, 13, 5, 6, 7, 8,
+];
+
+const fn square_numbers() -> [u8; 64] {
+    let mut numbers = [0; 64];
+    let mut i = 0;
+    while i < 32 {
+        numbers[BIT_INDICES[i] as usize] = i as u8 + 1;
+        i += 1;
+    }
+    numbers
+}
+
+/// Square number of each bit index, or 0 for bits that are not squares.
+const SQUARE_NUMBERS: [u8; 64] = square_numbers();
+
+/// A playable square, numbered 1 to 32 from Black's side as in PDN. Row 0 is
+/// the row of squares 1-4 and column 0 is the a file, so square 1 is b8 and
+/// square 32 is g1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Square(u8);
+
+impl Square {
+    pub const fn new(number: u8) -> Option<Square> {
+        if number >= 1 && number <= 32 {
+            Some(Square(number))
+        } else {
+            None
+        }
+    }
+
+    pub const fn from_bit_index(bit_index: u8) -> Option<Square> {
+        if bit_index >= 64 {
+            return None;
+        }
+        Square::new(SQUARE_NUMBERS[bit_index as usize])
+    }
+
+    /// The square at `row` and `column`, if it is a dark one.
+    pub const fn from_row_col(row: u8, column: u8) -> Option<Square> {
+        if row >= 8 || column >= 8 || (row + column).is_multiple_of(2) {
+            return None;
+        }
+        Some(Square(row * 4 + column / 2 + 1))
+    }
+
+    /// Parses coordinates such as `b8`, rejecting light squares.
+    pub fn from_algebraic(text: &str) -> Result<Square> {
+        let invalid = || Error::InvalidCoordinate(text.to_string());
+        let &[file, rank] = text.as_bytes() else {
+            return Err(invalid());
+        };
+        if !(b'a'..=b'h').contains(&file.to_ascii_lowercase()) || !(b'1'..=b'8').contains(&rank) {
+            return Err(invalid());
+        }
+        Square::from_row_col(b'8' - rank, file.to_ascii_lowercase() - b'a').ok_or_else(invalid)
+    }
+
+    pub const fn number(self) -> u8 {
+        self.0
+    }
+
+    pub const fn bit_index(self) -> u8 {
+        BIT_INDICES[self.0 as usize - 1]
+    }
+
+    // in the padded layout a diagonal step is 4 or 5 bits and a jump twice
+    // that; moves that wrap across a row edge go through a ghost bit
+    /// Whether `to` is one diagonal step from `from`.
+    pub const fn is_step(from: Square, to: Square) -> bool {
+        let distance = from.bit_index().abs_diff(to.bit_index());
+        distance == 4 || distance == 5
+    }
+
+    /// The square jumped over going from `from` to `to`, if that is a jump.
+    pub const fn jumped_between(from: Square, to: Square) -> Option<Square> {
+        let (from, to) = (from.bit_index(), to.bit_index());
+        match from.abs_diff(to) {
+            8 | 10 => Square::from_bit_index((from + to) / 2),
+            _ => None,
+        }
+    }
+
+    pub fn bitboard(self) -> Bitboard {
+        Bitboard::from(1u64 << self.bit_index())
+    }
+
+    pub const fn row(self) -> u8 {
+        (self.0 - 1) / 4
+    }
+
+    pub const fn column(self) -> u8 {
+        let row = self.row();
+        // even rows start with a light square
+        (self.0 - 1) % 4 * 2 + (row + 1) % 2
+    }
+
+    pub fn algebraic(self) -> String {
+        let file = (b'a' + self.column()) as char;
+        let rank = (b'8' - self.row()) as char;
+        format!("{file}{rank}")
+    }
+}
+
+impl TryFrom<u8> for Square {
+    type Error = Error;
+
+    fn try_from(number: u8) -> Result<Square> {
+        Square::new(number).ok_or(Error::InvalidSquare(number))
+    }
+}
+
+impl From<Square> for u8 {
+    fn from(square: Square) -> u8 {
+        square.0
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::square::Square;
+
+    #[test]
+    fn test_square_conversions() {
+        for n in 1..=32 {
+            let square = Square::new(n).unwrap();
+            assert_eq!(Square::from_bit_index(square.bit_index()), Some(square));
+            assert_eq!(
+                Square::from_row_col(square.row(), square.column()),
+                Some(square)
+            );
+            assert_eq!(Square::from_algebraic(&square.algebraic()).unwrap(), square);
+        }
+        let corners = [(1, "b8"), (4, "h8"), (5, "a7"), (29, "a1"), (32, "g1")];
+        for (n, algebraic) in corners {
+            assert_eq!(Square::new(n).unwrap().algebraic(), algebraic);
+        }
+    }
+
+    #[test]
+    fn test_steps_and_jumps() {
+        for from in (1..=32).filter_map(Square::new) {
+            for to in (1..=32).filter_map(Square::new) {
+                let rows = from.row().abs_diff(to.row());
+                let columns = from.column().abs_diff(to.column());
+                assert_eq!(Square::is_step(from, to), rows == 1 && columns == 1);
+                let jumped = Square::jumped_between(from, to);
+                assert_eq!(jumped.is_some(), rows == 2 && columns == 2);
+                if let Some(jumped) = jumped {
+                    assert!(Square::is_step(from, jumped) && Square::is_step(jumped, to));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_squares() {
+        assert_eq!(Square::new(0), None);
+        assert_eq!(Square::new(33), None);
+        assert!(matches!(
+            Square::try_from(40),
+            Err(Error::InvalidSquare(40))
+        ));
+        for bit in [0, 9, 18, 27, 36, 41, 63, 64] {
+            assert_eq!(Square::from_bit_index(bit), None, "bit= {bit}");
+        }
+        assert_eq!(Square::from_row_col(0, 0), None);
+        assert_eq!(Square::from_row_col(8, 1), None);
+        for text in ["a8", "i1", "b9", "b", "b10", ""] {
+            assert!(
+                matches!(
+                    Square::from_algebraic(text),
+                    Err(Error::InvalidCoordinate(_))
+                ),
+                "text= {text:?}"
+            );
+        }
+    }
+}
@@ -1,75 +1,14 @@
-pub fn notation_bit_index(notation: u8) -> u8 {
-    match notation {
-        1 => 37,
-        2 => 38,
-        3 => 39,
-        4 => 40,
-        5 => 32,
-        6 => 33,
-        7 => 34,
-        8 => 35,
-        9 => 28,
-        10 => 29,
-        11 => 30,
-        12 => 31,
-        13 => 23,
-        14 => 24,
-        15 => 25,
-        16 => 26,
-        17 => 19,
-        18 => 20,
-        19 => 21,
-        20 => 22,
-        21 => 14,
-        22 => 15,
-        23 => 16,
-        24 => 17,
-        25 => 10,
-        26 => 11,
-        27 => 12,
-        28 => 13,
-        29 => 5,
-        30 => 6,
-        31 => 7,
-        32 => 8,
-        _ => panic!("Invalid notation: {}", notation),
-    }
+use crate::error::{Error, Result};
+use crate::square::Square;
+
+/// Bit index of a square number, see [`Square::bit_index`].
+pub fn notation_bit_index(notation: u8) -> Result<u8> {
+    Ok(Square::try_from(notation)?.bit_index())
 }
 
-pub fn bit_index_notation(bit_index: u8) -> u8 {
-    match bit_index {
-        37 => 1,
-        38 => 2,
-        39 => 3,
-        40 => 4,
-        32 => 5,
-        33 => 6,
-        34 => 7,
-        35 => 8,
-        28 => 9,
-        29 => 10,
-        30 => 11,
-        31 => 12,
-        23 => 13,
-        24 => 14,
-        25 => 15,
-        26 => 16,
-        19 => 17,
-        20 => 18,
-        21 => 19,
-        22 => 20,
-        14 => 21,
-        15 => 22,
-        16 => 23,
-        17 => 24,
-        10 => 25,
-        11 => 26,
-        12 => 27,
-        13 => 28,
-        5 => 29,
-        6 => 30,
-        7 => 31,
-        8 => 32,
-        _ => panic!("Invalid bit index: {}", bit_index),
-    }
+/// Square number at a bit index, see [`Square::from_bit_index`].
+pub fn bit_index_notation(bit_index: u8) -> Result<u8> {
+    Square::from_bit_index(bit_index)
+        .map(Square::number)
+        .ok_or(Error::InvalidBitIndex(bit_index))
 }
@@ -0,0 +1,95 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+const ENTRY_SIZE: usize = std::mem::size_of::<Entry>();
+
+#[derive(Default)]
+struct Entry {
+    // `check` holds the key xor the stored count, so a torn write from two
+    // threads racing on the same slot fails verification instead of
+    // returning a count for the wrong position
+    check: AtomicU64,
+    nodes: AtomicU64,
+}
+
+/// Fixed-size, lock-free table mapping position keys to perft node counts.
+/// Entries are always replaced on store.
+pub struct TranspositionTable {
+    entries: Vec<Entry>,
+    mask: usize,
+}
+
+impl TranspositionTable {
+    /// Creates a table using at most `size_mb` megabytes, rounded down to a
+    /// power-of-two number of entries.
+    pub fn new(size_mb: usize) -> Self {
+        let requested = (size_mb * 1024 * 1024 / ENTRY_SIZE).max(1);
+        let len = 1 << (usize::BITS - 1 - requested.leading_zeros());
+        let mut entries = Vec::with_capacity(len);
+        entries.resize_with(len, Entry::default);
+        TranspositionTable {
+            entries,
+            mask: len - 1,
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    #[inline]
+    pub fn probe(&self, key: u64) -> Option<u64> {
+        let entry = &self.entries[key as usize & self.mask];
+        let nodes = entry.nodes.load(Ordering::Relaxed);
+        let check = entry.check.load(Ordering::Relaxed);
+        if check ^ nodes == key {
+            Some(nodes)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub fn store(&self, key: u64, nodes: u64) {
+        let entry = &self.entries[key as usize & self.mask];
+        entry.check.store(key ^ nodes, Ordering::Relaxed);
+        entry.nodes.store(nodes, Ordering::Relaxed);
+    }
+
+    pub fn clear(&self) {
+        for entry in &self.entries {
+            entry.check.store(0, Ordering::Relaxed);
+            entry.nodes.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::transposition::TranspositionTable;
+
+    #[test]
+    fn test_table_size() {
+        assert_eq!(TranspositionTable::new(1).len(), 65536);
+        assert_eq!(TranspositionTable::new(3).len(), 131072);
+        assert_eq!(TranspositionTable::new(0).len(), 1);
+    }
+
+    #[test]
+    fn test_probe_store() {
+        let tt = TranspositionTable::new(1);
+        let key = 0x1234_5678_9abc_def0;
+        assert_eq!(tt.probe(key), None);
+        tt.store(key, 42);
+        assert_eq!(tt.probe(key), Some(42));
+        // same slot, different key
+        assert_eq!(tt.probe(key ^ (1 << 40)), None);
+        tt.clear();
+        assert_eq!(tt.probe(key), None);
+    }
+}
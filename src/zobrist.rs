@@ -0,0 +1,145 @@
+use crate::bitboard::Bitboard;
+use crate::board::{Board, Color, PLAYABLE};
+use crate::r#move::Move;
+
+const BLACK_MAN: usize = 0;
+const WHITE_MAN: usize = 1;
+const BLACK_KING: usize = 2;
+const WHITE_KING: usize = 3;
+
+const fn splitmix64(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    (state, z ^ (z >> 31))
+}
+
+// keys are indexed by bit index in the padded layout; bits outside
+// `PLAYABLE` keep a zero key so they never affect the hash
+const fn piece_keys() -> [[u64; 64]; 4] {
+    let mut keys = [[0; 64]; 4];
+    let mut state = 0x2545f4914f6cdd1d;
+    let mut piece = 0;
+    while piece < 4 {
+        let mut bit = 0;
+        while bit < 64 {
+            if PLAYABLE & (1 << bit) != 0 {
+                let (next, key) = splitmix64(state);
+                state = next;
+                keys[piece][bit] = key;
+            }
+            bit += 1;
+        }
+        piece += 1;
+    }
+    keys
+}
+
+const PIECE_KEYS: [[u64; 64]; 4] = piece_keys();
+
+/// Mixed into the key when White is to move.
+pub const WHITE_TO_MOVE: u64 = splitmix64(0x6a09e667f3bcc908).1;
+
+#[inline]
+fn piece_kind(color: Color, king: bool) -> usize {
+    match (color, king) {
+        (Color::Black, false) => BLACK_MAN,
+        (Color::White, false) => WHITE_MAN,
+        (Color::Black, true) => BLACK_KING,
+        (Color::White, true) => WHITE_KING,
+    }
+}
+
+#[inline]
+fn squares_key(kind: usize, squares: Bitboard) -> u64 {
+    let mut key = 0;
+    for square in squares {
+        key ^= PIECE_KEYS[kind][square.trailing_zeros() as usize];
+    }
+    key
+}
+
+/// Computes the Zobrist key of `board` with `color` to move from scratch.
+pub fn hash(board: &Board, color: Color) -> u64 {
+    let mut key = squares_key(BLACK_MAN, board.bp & !board.kings)
+        ^ squares_key(WHITE_MAN, board.wp & !board.kings)
+        ^ squares_key(BLACK_KING, board.bp & board.kings)
+        ^ squares_key(WHITE_KING, board.wp & board.kings);
+    if color == Color::White {
+        key ^= WHITE_TO_MOVE;
+    }
+    key
+}
+
+/// Returns the key after `color` plays `m` on `board`, mirroring
+/// [`Board::apply_move`]. `board` is the position before the move is applied.
+/// The side to move is switched as well.
+#[inline]
+pub fn update_move(key: u64, board: &Board, color: Color, m: &Move) -> u64 {
+    let king = !(m.movers & board.kings).is_empty();
+    let mut key = key ^ squares_key(piece_kind(color, king), m.movers);
+    let opponent = color.opposite();
+    key ^= squares_key(piece_kind(opponent, false), m.jumped & !board.kings);
+    key ^= squares_key(piece_kind(opponent, true), m.jumped & board.kings);
+    key ^ WHITE_TO_MOVE
+}
+
+/// Returns the key after [`Board::promote_kings`] crowned `new_kings`.
+/// `board` may be the position either before or after the promotion.
+#[inline]
+pub fn update_promotion(key: u64, board: &Board, new_kings: Bitboard) -> u64 {
+    if new_kings.is_empty() {
+        return key;
+    }
+    let black = new_kings & board.bp;
+    let white = new_kings & board.wp;
+    key ^ squares_key(BLACK_MAN, black)
+        ^ squares_key(BLACK_KING, black)
+        ^ squares_key(WHITE_MAN, white)
+        ^ squares_key(WHITE_KING, white)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        board::{Board, Color},
+        fen::parse_fen,
+        move_generator::MoveGenerator,
+        zobrist::{hash, update_move, update_promotion},
+    };
+
+    fn check_incremental(color: Color, board: &Board, key: u64, depth: u32) {
+        assert_eq!(key, hash(board, color), "board= {board}");
+        if depth == 0 {
+            return;
+        }
+        for m in MoveGenerator::new(*board, color).generate_moves() {
+            let mut new_board = *board;
+            let mut new_key = update_move(key, &new_board, color, &m);
+            new_board.apply_move(color, &m);
+            let new_kings = new_board.promote_kings();
+            new_key = update_promotion(new_key, &new_board, new_kings);
+            check_incremental(color.opposite(), &new_board, new_key, depth - 1);
+        }
+    }
+
+    #[test]
+    fn test_zobrist_incremental_matches_full() {
+        let board = Board::new();
+        check_incremental(Color::Black, &board, hash(&board, Color::Black), 6);
+
+        let (board, color) = parse_fen("W:W18,24,27,28,K10,K15:B12,16,20,K22,K25,K29").unwrap();
+        check_incremental(color, &board, hash(&board, color), 5);
+
+        // men one step from promotion on both sides
+        let (board, color) = parse_fen("B:W6,7,K19:B26,27,K14").unwrap();
+        check_incremental(color, &board, hash(&board, color), 5);
+    }
+
+    #[test]
+    fn test_zobrist_side_to_move() {
+        let board = Board::new();
+        assert_ne!(hash(&board, Color::Black), hash(&board, Color::White));
+    }
+}
@@ -1,55 +1,26 @@
 use checkers_perft::{
-    board::{Board, Color},
+    archive::GameReader,
     game_json::Game,
-    move_generator::MoveGenerator,
+    replay::{validate, ReplayError},
 };
 
 #[cfg(test)]
 fn test_game(game: Game) {
-    let mut board = Board::new();
-    for m in game.moves {
-        let generated_moves = MoveGenerator::new(board, Color::Black).generate_moves();
-        let black_move = m.black.into_move();
-        assert!(
-            generated_moves.contains(&black_move),
-            "number= {}\nheaders= {}\nblack_move= {}\ngenerated_moves= {:?}\nboard= {}",
-            m.number,
+    if let Err(e) = validate(&game) {
+        let diagram = match &e {
+            ReplayError::Illegal(illegal) => illegal.board.diagram().with_numbers(true).to_string(),
+            ReplayError::Invalid(_) => String::new(),
+        };
+        panic!(
+            "headers= {}\n{}\n{}",
             game.headers
                 .iter()
                 .map(|h| format!("{}: {}", h.name, h.value))
                 .collect::<Vec<_>>()
                 .join(", "),
-            black_move.to_string(),
-            generated_moves
-                .iter()
-                .map(|m| m.to_string())
-                .collect::<Vec<_>>(),
-            board.to_string()
+            e,
+            diagram
         );
-        board.apply_move(Color::Black, &black_move);
-        board.promote_kings();
-        if let Some(white_move_detail) = m.white {
-            let white_move = white_move_detail.into_move();
-            let generated_moves = MoveGenerator::new(board, Color::White).generate_moves();
-            assert!(
-                generated_moves.contains(&white_move),
-                "number= {}\nheaders= {}\nwhite_move= {}\ngenerated_moves= {:?}\nboard= {}",
-                m.number,
-                game.headers
-                    .iter()
-                    .map(|h| format!("{}: {}", h.name, h.value))
-                    .collect::<Vec<_>>()
-                    .join(", "),
-                white_move.to_string(),
-                generated_moves
-                    .iter()
-                    .map(|m| m.to_string())
-                    .collect::<Vec<_>>(),
-                board.to_string()
-            );
-            board.apply_move(Color::White, &white_move);
-            board.promote_kings();
-        }
     }
 }
 
@@ -81,11 +52,19 @@ fn test_edin8() {
     test_game(game);
 }
 
+#[test]
+fn test_archive_streaming() {
+    for file in ["games.pdn", "game1.json"] {
+        let path = format!("{}/tests/fixtures/{file}", env!("CARGO_MANIFEST_DIR"));
+        for game in GameReader::open(path).expect("Failed to open fixture") {
+            test_game(game.expect("Failed to read game"));
+        }
+    }
+}
+
 // #[test]
 // fn test_oca() {
 //     let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/oca.json");
-//     let games = parse_games_from_json_array_file(path).expect("Failed to parse game JSON array");
-//     for game in games {
-//         test_game(game);
-//     }
+//     let summary = validate_games(GameReader::open(path).expect("Failed to open archive"));
+//     assert!(summary.failures.is_empty(), "{:?}", summary.failures);
 // }
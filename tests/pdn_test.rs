@@ -0,0 +1,36 @@
+use checkers_perft::{
+    game_json::Game,
+    pdn::{parse_games_from_pdn_file, write_pdn, PdnReader},
+};
+
+#[test]
+fn test_pdn_matches_json() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/games.pdn");
+    let games = PdnReader::from_file(path)
+        .expect("Failed to open PDN")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("Failed to parse PDN");
+    assert_eq!(games.len(), 4);
+
+    for (game, name) in games.iter().zip(["game1", "game2", "alwick", "edin8"]) {
+        let path = format!(
+            "{}/tests/fixtures/{}.json",
+            env!("CARGO_MANIFEST_DIR"),
+            name
+        );
+        let expected = Game::from_file(path).expect("Failed to parse game JSON");
+        assert_eq!(game, &expected, "{name}");
+    }
+}
+
+#[test]
+fn test_pdn_round_trip() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/games.pdn");
+    let games = parse_games_from_pdn_file(path).expect("Failed to parse PDN");
+    let mut written = Vec::new();
+    write_pdn(&mut written, &games).expect("Failed to write PDN");
+    let reparsed = PdnReader::new(written.as_slice())
+        .collect::<Result<Vec<_>, _>>()
+        .expect("Failed to parse written PDN");
+    assert_eq!(reparsed, games);
+}
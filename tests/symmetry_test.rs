@@ -0,0 +1,106 @@
+use checkers_perft::{
+    bitboard::Bitboard,
+    board::{Board, Color, PLAYABLE},
+    fen::parse_fen,
+    move_generator::MoveGenerator,
+    perft::{divide, perft},
+};
+
+const POSITIONS: &[&str] = &[
+    "B:W21-32:B1-12",
+    "W:W18,24,27,28,K10,K15:B12,16,20,K22,K25,K29",
+    "B:W6,7,K14,K23:B19,22,26,K11",
+    "B:W9,10,11,18,19:BK23",
+    "W:W27:B8,15,23",
+];
+
+fn flip_square(n: u8) -> u8 {
+    33 - n
+}
+
+#[test]
+fn test_rotation_maps_squares() {
+    for n in 1..=32 {
+        assert_eq!(
+            Bitboard::try_from_notation_vector(&[n]).unwrap().rotated(),
+            Bitboard::try_from_notation_vector(&[flip_square(n)]).unwrap()
+        );
+    }
+    assert_eq!(Bitboard::from(PLAYABLE).rotated(), Bitboard::from(PLAYABLE));
+}
+
+#[test]
+fn test_flip_is_an_involution() {
+    for fen in POSITIONS {
+        let (board, _) = parse_fen(fen).unwrap();
+        assert_eq!(board.flipped().flipped(), board, "fen= {fen}");
+        assert!(board.flipped().validate().is_empty(), "fen= {fen}");
+    }
+    assert_eq!(Board::new().flipped(), Board::new());
+}
+
+#[test]
+fn test_moves_match_under_flip() {
+    for fen in POSITIONS {
+        let (board, color) = parse_fen(fen).unwrap();
+        let flipped = board.flipped();
+        let mut expected: Vec<Vec<u8>> = MoveGenerator::new(board, color)
+            .generate_moves()
+            .iter()
+            .map(|m| {
+                let origin = m.origin().expect("generated moves record a path");
+                std::iter::once(origin)
+                    .chain(m.path.as_notation_vector())
+                    .map(flip_square)
+                    .collect()
+            })
+            .collect();
+        let mut found: Vec<Vec<u8>> = MoveGenerator::new(flipped, color.opposite())
+            .generate_moves()
+            .iter()
+            .map(|m| {
+                std::iter::once(m.origin().unwrap())
+                    .chain(m.path.as_notation_vector())
+                    .collect()
+            })
+            .collect();
+        expected.sort();
+        found.sort();
+        assert_eq!(found, expected, "fen= {fen}");
+    }
+}
+
+#[test]
+fn test_perft_invariant_under_flip() {
+    for fen in POSITIONS {
+        let (board, color) = parse_fen(fen).unwrap();
+        let flipped = board.flipped();
+        for depth in 0..=6 {
+            assert_eq!(
+                perft(color.opposite(), &flipped, depth),
+                perft(color, &board, depth),
+                "fen= {fen}, depth= {depth}"
+            );
+        }
+
+        let mut nodes: Vec<u64> = divide(color, &board, 5).iter().map(|e| e.nodes).collect();
+        let mut flipped_nodes: Vec<u64> = divide(color.opposite(), &flipped, 5)
+            .iter()
+            .map(|e| e.nodes)
+            .collect();
+        nodes.sort();
+        flipped_nodes.sort();
+        assert_eq!(flipped_nodes, nodes, "fen= {fen}");
+    }
+}
+
+#[test]
+fn test_initial_position_is_symmetric() {
+    let board = Board::new();
+    for depth in 0..=7 {
+        assert_eq!(
+            perft(Color::White, &board.flipped(), depth),
+            perft(Color::Black, &board, depth)
+        );
+    }
+}